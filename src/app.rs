@@ -1,9 +1,10 @@
 //! This module contains support for the business logic of the application's UI. This includes input
 //! handling events and reactive changes to the persistent state of the application.
 
-use std::time::Duration;
+mod headless;
+
+use std::{cmp::Reverse, path::PathBuf, sync::Arc, time::Duration};
 
-use clap::Parser as _;
 use color_eyre::{
     eyre::{eyre, OptionExt as _},
     Result,
@@ -12,28 +13,43 @@ use fastrand::Rng;
 use ratatui::{
     crossterm::event::{poll, read, Event, KeyCode},
     prelude::{Buffer, Rect, Widget as _},
-    text::Line,
     widgets::Clear,
     DefaultTerminal,
 };
 use regex::Regex;
-use ureq::agent;
 
-use crate::utils::{
-    self, ChatCompletionResponse, Cli, EndMenuItem, GameItem, GameScreen, MainMenuItem,
-    ModelMenuDirection, OperationType, OptionsMenuItem, RandomResult, Request, Screen,
+use crate::{
+    cache::ModelCache,
+    config::{Config, Difficulty, HintStyle, ModelFilter, Sampling, Theme},
+    daily::Daily,
+    error::ApiError,
+    fuzzy::fuzzy_match,
+    history::{History, HistoryEntry},
+    llm::{ChatBackend, Fallback, Offline, OpenRouter, Reply, OFFLINE_MODEL},
+    persona::{Persona, Personas},
+    template::Template,
+    transcript::Transcript,
+    utils::{
+        parse_number, parse_range, Classic, Cli, EndMenuItem, ErrorPopupItem, GameItem, GameMode,
+        GameScreen, MainMenuItem, MenuOrigin, ModelInfo, ModelMenuDirection, ModelSection, Number,
+        OperationType, OptionsMenuItem, Overlay, RandomResult, Request, Round, SamplingMenuItem,
+        Screen, Usage, DEFAULT_BASE_URL, RANGE_PATTERN, RECENT_MODELS,
+    },
+    worker::{ModelRefresh, RetryPolicy, Worker, WorkerMessage},
 };
 
 /// This structure holds information about the application itself, keeping inside it both state and
 /// functions relative to the drawing and updating of the state.
-pub struct App<'line> {
+pub struct App {
     /// This field refers to the condition of the game being run.
     pub(crate) exit: bool,
     /// This field refers to the current screen in which the user finds himself, generally as a
     /// consequence of a prior keypress.
     pub(crate) screen: Screen,
     /// This field refers to the score accumulated by the user when playing multiple games in a row.
-    pub(crate) score: u8,
+    pub(crate) score: u32,
+    /// This field refers to the amount of games in a row the user has guessed correctly.
+    pub(crate) streak: u32,
     /// This field refers to the ranged input taken from the user during the in-game experience.
     pub(crate) range_input: String,
     /// This field refers to the regular guess input taken from the user during the in-game
@@ -43,134 +59,763 @@ pub struct App<'line> {
     /// range and thus having determined whether they are right or wrong. This may not be
     /// initialized until a game is actually played, so it's wrapped in an [`Option`].
     pub(crate) result: Option<RandomResult>,
+    /// This field refers to the summary of the round being played, which is kept around so that the
+    /// chat completion request can be retried. This may not be initialized until a game is actually
+    /// played, so it's wrapped in an [`Option`].
+    pub(crate) round: Option<Round>,
+    /// This field refers to the game mode being played.
+    pub(crate) mode: GameMode,
+    /// This field refers to the state of the round of the classic game mode being played, if any.
+    /// It is only set between the first guess of the round and the last one, or throughout the
+    /// daily challenge.
+    pub(crate) classic: Option<Classic>,
+    /// This field refers to the daily challenge of the day, if it's being played.
+    pub(crate) daily: Option<Daily>,
+    /// This field refers to how the hints of the classic game mode are given.
+    pub(crate) hints: HintStyle,
+    /// This field refers to the reason why the last chat completion request failed, which is shown
+    /// in the error popup. This is only set while the popup is on display.
+    pub(crate) error: Option<ApiError>,
+    /// This field refers to the notice shown at the bottom of the screen about something that went
+    /// wrong without stopping the game, if any. It's cleared on the next key press.
+    pub(crate) notice: Option<String>,
     /// This field refers to the model selected by the user to process the request to make to the
     /// OpenRouter API for chat completion.
     pub(crate) model: String,
-    /// This field refers to the complete set of models retrieved from the OpenRouter API which are
-    /// available for use in the menu.
-    pub(crate) models: Vec<String>,
-    /// This field refers to the set of models that are currently in display within the viewport of
-    /// the TUI. This is part of the persistent state required for the scrolling feature.
-    pub(crate) models_view: Vec<Line<'line>>,
-    /// This field refers to the set of selectors / spaces to display which model is currently
-    /// selected to be used. This is part of the persistent state required for the scrolling
+    /// This field refers to the complete set of models retrieved from the OpenRouter API, along with
+    /// their details. It starts off as the cached list, if any, so that the cost of the replies can
+    /// be estimated before the model menu is ever entered.
+    pub(crate) catalog: Vec<ModelInfo>,
+    /// This field refers to the language models the chat completion request falls back to, in
+    /// order, when the selected one fails.
+    pub(crate) fallback_models: Vec<String>,
+    /// This field refers to the sampling parameters sent along with the chat completion request.
+    pub(crate) sampling: Sampling,
+    /// This field refers to the amount of tokens the requests of the current round took up and what
+    /// they cost, if reported.
+    pub(crate) round_usage: Option<Usage>,
+    /// This field refers to the amount of tokens the requests of the session took up and what they
+    /// cost.
+    pub(crate) session_usage: Usage,
+    /// This field refers to the most the session may spend on replies, in US dollars, before the
+    /// rest of them are picked from the phrase bank.
+    pub(crate) spend_cap: Option<f64>,
+    /// This field refers to the language model that answered the last chat completion request, or
+    /// the one the request in flight is being made with.
+    pub(crate) reply_model: String,
+    /// This field refers to the set of models which are available for use in the menu; namely, those
+    /// in the catalog that make it through the filters.
+    pub(crate) models: Vec<ModelInfo>,
+    /// This field refers to the filters applied to the catalog of models to list in the menu.
+    pub(crate) model_filter: ModelFilter,
+    /// This field refers to the identifiers of the models starred in the model menu.
+    pub(crate) favorite_models: Vec<String>,
+    /// This field refers to the identifiers of the models picked most recently in the model menu,
+    /// from the latest one.
+    pub(crate) recent_models: Vec<String>,
+    /// This field refers to how many of the models listed in the menu are pinned at the start of
+    /// the list as favorites and as recently used ones, respectively, ahead of the full list.
+    pub(crate) pinned_models: (usize, usize),
+    /// This field refers to the cache of the catalog of models, which is used instead of fetching
+    /// the catalog while it's fresh.
+    pub(crate) model_cache: ModelCache,
+    /// This field refers to the handle of the catalog of models being fetched in the background, if
+    /// any.
+    pub(crate) model_refresh: Option<ModelRefresh>,
+    /// This field refers to the reason why the catalog of models could last not be fetched in the
+    /// background, if it failed.
+    pub(crate) model_refresh_error: Option<ApiError>,
+    /// This field refers to the position of the currently selected model in the viewport among the
+    /// models listed in the menu. This is part of the persistent state required for the scrolling
     /// feature.
-    pub(crate) selectors_view: Vec<Line<'line>>,
-    /// This field refers to the currently selected model in the viewport. This is part of the
-    /// persistent state required for the scrolling feature.
-    pub(crate) model_view_selected: String,
+    pub(crate) model_view_selected: usize,
     /// This field refers to the offset by which the first element of the viewport is not seen
     /// anymore. This is core to the scrolling feature and is thus part of the persistent state.
-    pub(crate) model_view_offset: u16,
-    /// This field refers to the API key to be used when performing the chat completion request to
-    /// the OpenRouter API.
-    pub(crate) api_key: String,
+    pub(crate) model_view_offset: usize,
+    /// This field refers to the amount of models that fit in the viewport as of the last time it was
+    /// drawn, which is how far a page up or down moves the selection.
+    pub(crate) model_view_height: usize,
+    /// This field refers to the query typed in to search the model menu, which is only set while
+    /// searching.
+    pub(crate) model_query: Option<String>,
+    /// This field refers to the contents of the configuration file, which are written back to it
+    /// whenever the user changes a setting in-game.
+    pub(crate) config: Config,
+    /// This field refers to the path to the configuration file, if there's any place to keep it.
+    pub(crate) config_path: Option<PathBuf>,
+    /// This field refers to the transcript of the exchanges with the language model in the session,
+    /// part of which is sent along with each request.
+    pub(crate) transcript: Transcript,
+    /// This field refers to the rounds played so far, which are kept across runs to compute the
+    /// statistics.
+    pub(crate) history: History,
+    /// This field refers to the difficulty of the game, which determines the range the range prompt
+    /// is filled with.
+    pub(crate) difficulty: Difficulty,
+    /// This field refers to the personas the language model may speak as, both the built-in ones
+    /// and those defined by the user, along with the one it speaks as.
+    pub(crate) personas: Personas,
+    /// This field refers to the template of the message telling the language model about each
+    /// round.
+    pub(crate) user_prompt: Template,
+    /// This field refers to the name the user goes by in the templates.
+    pub(crate) player: String,
+    /// This field refers to the colors the interface is drawn with.
+    pub(crate) theme: Theme,
+    /// This field refers to the language model provider used to perform the chat completion request
+    /// and to fetch the list of available models.
+    pub(crate) backend: Arc<dyn ChatBackend>,
+    /// This field refers to the built-in phrase bank, which the user may fall back to when the chat
+    /// completion request fails.
+    pub(crate) offline: Arc<dyn ChatBackend>,
     /// This field refers to the regular expression in use to validate the input of the user in the
-    /// ranged numbers prompt.
+    /// ranged numbers prompt, which follows the syntax of the ranges of Rust.
     pub(crate) ranged_re: Regex,
     /// This field refers to the regular expression in use to validate the input of the user in the
     /// regular guess number prompt.
     pub(crate) input_re: Regex,
-    /// This field refers to the flag that allows notifying the user their input is invalid.
-    pub(crate) extra_line_help: bool,
-    /// This field refers to the flag that allows notifying the user the request is being processed.
-    pub(crate) processing_request: bool,
+    /// This field refers to the reason the input of the user is invalid, if it is, so that they can
+    /// be notified.
+    pub(crate) input_error: Option<String>,
+    /// This field refers to the handle of the chat completion request being processed in the
+    /// background, if any. It also allows notifying the user the request is being processed.
+    pub(crate) request_worker: Option<Worker>,
+    /// This field refers to the policy followed to retry the chat completion request when it fails.
+    pub(crate) retry_policy: RetryPolicy,
+    /// This field refers to the number of the attempt at the chat completion request currently in
+    /// flight, starting from one.
+    pub(crate) attempt: u32,
+    /// This field refers to the amount of iterations the main loop has gone through, and is used to
+    /// animate the spinner shown while the request is being processed.
+    pub(crate) tick: usize,
     /// This field refers to the RNG to be used when the user's input is processed and the result of
     /// their guess is computed.
     pub(crate) rng: Rng,
+    /// This field refers to the seed the RNG was created with.
+    pub(crate) seed: u64,
+    /// This field refers to the overlay drawn on top of the screen, if any.
+    pub(crate) overlay: Option<Overlay>,
     /// This field refers to the output of the chat completion request, holding only the message
     /// retrieved from the language model's response.
     pub(crate) chat_completion_output: String,
+    /// This field refers to whether the message of the language model should be streamed and
+    /// rendered as it arrives.
+    pub(crate) stream: bool,
 }
 
-impl App<'_> {
-    /// This function serves as a means of validating user input for the range and guess.
-    fn validate_input(&self) -> Result<bool> {
-        if self.ranged_re.is_match(&self.range_input) && self.input_re.is_match(&self.input) {
-            // process the ranged input
-            let (start, end) = self.range_input.split_at(
-                self.range_input
-                    .find("..")
-                    .ok_or_eyre("validate_input parsing failed")?,
-            );
-            let end: String = end.chars().rev().collect();
-            let (end, _) =
-                end.split_at(end.find("..").ok_or_eyre("validate_input parsing failed")?);
-            let start: usize = start.parse()?;
-            let end: usize = end.parse()?;
-            let flag1 = start < end;
+impl App {
+    /// This function builds the application out of the arguments given in the command-line and the
+    /// settings in the configuration file, with the former taking precedence. If a model was given,
+    /// it is checked against the list of models the backend makes available.
+    ///
+    /// # Errors
+    ///
+    /// - [`ureq::Error`]
+    /// - [`regex::Error`]
+    /// - [`std::io::Error`] if the configuration file, the API key file or a persona file can't be
+    ///   read. The history file is not among them, as the game starts with an empty history if it
+    ///   can't be read.
+    /// - [`toml::de::Error`] if the configuration file or a persona file is not valid.
+    /// - [`color_eyre::Report`] if the given model or persona is not available, or if no API key was
+    ///   given for the OpenRouter API.
+    pub fn new(cli: &Cli) -> Result<Self> {
+        let (config_path, config) = Self::config(cli)?;
+        let model = cli.model().or_else(|| config.model());
+        let difficulty = cli.difficulty().unwrap_or_else(|| config.difficulty());
+        let personas = Personas::select(
+            Persona::library(
+                config_path.as_deref().and_then(Persona::dir).as_deref(),
+                config.system_prompt(),
+            )?,
+            cli.persona().or_else(|| config.persona()),
+        )?;
+
+        let (history, history_notice) = Self::history();
+        let seed = Self::seed(cli, &config);
+        let mut rng = Rng::with_seed(seed);
+        let offline: Arc<dyn ChatBackend> = Arc::new(Offline::new(rng.fork()));
+        let (backend, model_cache) = Self::backend(cli, &config, model, &offline)?;
+
+        Ok(Self {
+            exit: false,
+            screen: Screen::MainMenu(MainMenuItem::Play),
+            score: 0,
+            streak: 0,
+            result: None,
+            round: None,
+            mode: GameMode::OneShot,
+            classic: None,
+            daily: None,
+            hints: cli.hints().unwrap_or_else(|| config.hints()),
+            error: None,
+            notice: history_notice.or_else(|| Self::default_range_notice(&config)),
+            range_input: config
+                .default_range()
+                .cloned()
+                .unwrap_or_else(|| difficulty.range().to_owned()),
+            input: String::new(),
+            model: if cli.offline() {
+                OFFLINE_MODEL.to_owned()
+            } else {
+                model
+                    .cloned()
+                    .unwrap_or_else(|| "qwen/qwen3-32b:free".to_owned())
+            },
+            catalog: model_cache.cached_models(),
+            fallback_models: Self::fallback_models(cli, &config),
+            reply_model: String::new(),
+            sampling: cli.sampling().or(config.sampling()),
+            round_usage: None,
+            session_usage: Usage::default(),
+            spend_cap: cli.spend_cap().or_else(|| config.spend_cap()),
+            models: Vec::new(),
+            model_filter: config.model_filter(),
+            favorite_models: config.favorite_models().to_vec(),
+            recent_models: config.recent_models().to_vec(),
+            pinned_models: (0, 0),
+            model_cache,
+            model_refresh: None,
+            model_refresh_error: None,
+            model_view_selected: 0,
+            model_view_offset: 0,
+            model_view_height: 0,
+            model_query: None,
+            history,
+            transcript: Transcript::new(
+                cli.memory()
+                    .unwrap_or_else(|| config.memory().turns())
+                    .try_into()?,
+                cli.memory_tokens()
+                    .unwrap_or_else(|| config.memory().tokens())
+                    .try_into()?,
+            ),
+            difficulty,
+            personas,
+            user_prompt: match config.user_prompt() {
+                Some(user_prompt) => user_prompt.clone(),
+                None => Template::parse("{summary}")?,
+            },
+            player: cli
+                .player()
+                .or_else(|| config.player())
+                .cloned()
+                .unwrap_or_else(|| "partner".to_owned()),
+            theme: config.colors(),
+            config,
+            config_path,
+            backend,
+            offline,
+            ranged_re: Regex::new(RANGE_PATTERN)?,
+            input_re: Regex::new(r"\A-?\d+\z")?,
+            input_error: None,
+            request_worker: None,
+            retry_policy: RetryPolicy::new(cli.max_attempts()),
+            attempt: 0,
+            tick: 0,
+            rng,
+            seed,
+            overlay: None,
+            chat_completion_output: String::new(),
+            stream: cli.stream(),
+        })
+    }
+
+    /// This function returns the path to the configuration file given in the command-line, or
+    /// otherwise the default one, along with the settings it holds, if it exists.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the configuration file can't be read.
+    /// - [`toml::de::Error`] if the configuration file is not valid.
+    fn config(cli: &Cli) -> Result<(Option<PathBuf>, Config)> {
+        let path = cli.config().cloned().or_else(Config::default_path);
+        let config = path
+            .as_deref()
+            .map(Config::load)
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok((path, config))
+    }
+
+    /// This function returns the language models to fall back to, as given in the command-line or
+    /// otherwise in the configuration file.
+    fn fallback_models(cli: &Cli, config: &Config) -> Vec<String> {
+        // There's nothing to fall back to from the phrase bank.
+        if cli.offline() {
+            Vec::new()
+        } else if cli.fallback_models().is_empty() {
+            config.fallback_models().to_vec()
+        } else {
+            cli.fallback_models().to_vec()
+        }
+    }
+
+    /// This function returns the seed of the RNG the numbers are drawn with, as given in the
+    /// command-line or otherwise in the configuration file, or a random one if none was given.
+    fn seed(cli: &Cli, config: &Config) -> u64 {
+        cli.seed()
+            .or_else(|| config.seed())
+            .unwrap_or_else(|| Rng::new().u64(..))
+    }
+
+    /// This function loads the history of the rounds played so far. The game carries on with an
+    /// empty history if it can't be read, in which case the notice telling the user about it is
+    /// returned along with it.
+    fn history() -> (History, Option<String>) {
+        let path = History::default_path();
+        match History::load(path.clone()) {
+            Ok(history) => (history, None),
+            Err(err) => (
+                History::new(path),
+                Some(format!("Failed to load the history: {err:#}")),
+            ),
+        }
+    }
+
+    /// This function returns the notice shown at the start of the game if the default range in the
+    /// configuration file leaves out its upper bound. Ranges used to include it whatever the syntax,
+    /// so such a range was most likely written before they followed the syntax of the ranges of
+    /// Rust.
+    fn default_range_notice(config: &Config) -> Option<String> {
+        config
+            .default_range()
+            .filter(|range| range.contains("..") && !range.contains("..="))
+            .map(|range| {
+                format!(
+                    "The default range {range} leaves out its upper bound, write it with ..= to \
+include it"
+                )
+            })
+    }
 
-            // process the guess input
-            let guess: usize = self.input.parse()?;
-            let flag2 = guess >= start && guess <= end;
+    /// This function builds the language model backend out of the arguments given in the
+    /// command-line and the settings in the configuration file, falling back to the given phrase
+    /// bank when the requests fail, along with the cache of its list of models. An API key is
+    /// required to use the OpenRouter API, and if a model was given, it is checked against the list
+    /// of models the backend makes available, which is taken from the cache if possible.
+    fn backend(
+        cli: &Cli,
+        config: &Config,
+        model: Option<&String>,
+        offline: &Arc<dyn ChatBackend>,
+    ) -> Result<(Arc<dyn ChatBackend>, ModelCache)> {
+        if cli.offline() {
+            return Ok(Self::model_source(cli, config, offline, None));
+        }
 
-            return Ok(flag1 && flag2);
+        let api_key = Self::api_key(cli, config)?;
+        if api_key.is_none() && Self::base_url(cli, config) == DEFAULT_BASE_URL {
+            return Err(eyre!("an API key is required to use the OpenRouter API"));
+        }
+        let (backend, cache) = Self::model_source(cli, config, offline, api_key);
+
+        if let Some(model) = model {
+            if !cache
+                .models(&*backend)?
+                .iter()
+                .any(|info| info.id() == model)
+            {
+                return Err(eyre!("invalid model"));
+            }
         }
 
-        Ok(false)
+        Ok((backend, cache))
     }
 
-    /// This function processes a random number in the range given by the user and stores the result
-    /// in the corresponding internal state of the application.
+    /// This function builds the language model backend as in [`App::backend`], authenticating with
+    /// the given API key if any, but without checking whether it can be played with. This is all
+    /// listing the models available takes.
+    fn model_source(
+        cli: &Cli,
+        config: &Config,
+        offline: &Arc<dyn ChatBackend>,
+        api_key: Option<String>,
+    ) -> (Arc<dyn ChatBackend>, ModelCache) {
+        if cli.offline() {
+            // The phrase bank lists its only model straight away, so there's nothing to cache.
+            let cache = ModelCache::new(None, OFFLINE_MODEL.to_owned(), config.model_cache_ttl());
+            return (Arc::clone(offline), cache);
+        }
+
+        let base_url = Self::base_url(cli, config);
+        let backend = OpenRouter::new(base_url, api_key);
+        let cache = ModelCache::new(
+            ModelCache::default_path(),
+            base_url.to_owned(),
+            config.model_cache_ttl(),
+        );
+
+        (
+            Arc::new(Fallback::new(Arc::new(backend), Arc::clone(offline))),
+            cache,
+        )
+    }
+
+    /// This function returns the base URL of the server the requests are made to, as given in the
+    /// command-line or otherwise in the configuration file, or that of the OpenRouter API if none
+    /// was given.
+    fn base_url<'url>(cli: &'url Cli, config: &'url Config) -> &'url str {
+        cli.base_url()
+            .or_else(|| config.base_url())
+            .map_or(DEFAULT_BASE_URL, String::as_str)
+    }
+
+    /// This function returns the API key to authenticate with, as given in the command-line or
+    /// otherwise in the configuration file, if any.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the API key file can't be read.
+    fn api_key(cli: &Cli, config: &Config) -> Result<Option<String>> {
+        match cli.api_key() {
+            Some(api_key) => Ok(Some(api_key.clone())),
+            None => config.api_key(),
+        }
+    }
+
+    /// This function parses the ranged input of the user into its lower and upper bounds, both of
+    /// which are inclusive.
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] if the input is not a valid range, as told by [`parse_range`].
+    pub(crate) fn parse_range(&self) -> Result<(Number, Number)> {
+        parse_range(&self.ranged_re, &self.range_input)
+    }
+
+    /// This function serves as a means of validating user input for the range and guess.
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] telling what's wrong with the input, if anything.
+    fn validate_input(&self) -> Result<()> {
+        let (start, end) = self.parse_range()?;
+        if !self.input_re.is_match(&self.input) {
+            return Err(eyre!(
+                "expected a whole number as the guess, got {:?}",
+                self.input
+            ));
+        }
+        let guess = parse_number(&self.input)?;
+        if !(start..=end).contains(&guess) {
+            return Err(eyre!("{guess} is not in the range {}", self.range_input));
+        }
+
+        Ok(())
+    }
+
+    /// This function processes a random number in the range given by the user and stores the summary
+    /// of the round in the corresponding internal state of the application.
     fn process_random(&mut self) -> Result<()> {
-        let (start, end) = self.range_input.split_at(
-            self.range_input
-                .find("..")
-                .ok_or_eyre("process_random parsing failed")?,
+        let (start, end) = self.parse_range()?;
+        let guess = parse_number(&self.input)?;
+
+        let random = self.rng.i128(start..=end);
+
+        let result = if guess == random {
+            RandomResult::Correct
+        } else {
+            RandomResult::Incorrect
+        };
+        let streak = match result {
+            RandomResult::Correct => self.streak + 1,
+            RandomResult::Incorrect => 0,
+        };
+        self.result = None;
+        self.round_usage = None;
+        self.round = Some(Round::new(
+            result,
+            guess,
+            random,
+            (start, end),
+            streak,
+            self.score,
+        ));
+
+        Ok(())
+    }
+
+    /// This function plays the guess of the user in the classic game mode. The number is drawn on the
+    /// first guess of the round, and every wrong guess is answered with a hint, voiced by the
+    /// language model if so configured, until the user runs out of guesses. Once the round is over,
+    /// its summary is stored in the corresponding internal state of the application and the chat
+    /// completion request is performed as in the other game mode. The game carries on if a guess in
+    /// the daily challenge can't be written to its file, in which case the user is notified.
+    fn process_classic(&mut self) -> Result<()> {
+        let guess = parse_number(&self.input)?;
+        if self.classic.is_none() {
+            let (start, end) = self.parse_range()?;
+            let secret = self.rng.i128(start..=end);
+            // The result of the previous round would otherwise be taken for that of this one.
+            self.result = None;
+            self.round_usage = None;
+            self.classic = Some(Classic::new(
+                secret,
+                (start, end),
+                self.difficulty.attempts((start, end)),
+            ));
+        }
+        let classic = self.classic.as_mut().ok_or_eyre("round not yet started")?;
+        let hint = classic.guess(guess);
+        if let Some(daily) = &mut self.daily {
+            // The guess still counts for the rest of the session if it can't be kept.
+            if let Err(err) = daily.guess(guess) {
+                self.notice = Some(format!("{err:#}"));
+            }
+        }
+        let round = Round::new(
+            if hint.is_some() {
+                RandomResult::Incorrect
+            } else {
+                RandomResult::Correct
+            },
+            guess,
+            classic.secret(),
+            classic.range(),
+            match hint {
+                Some(_) if !classic.is_over() => self.streak,
+                Some(_) => 0,
+                None => self.streak + 1,
+            },
+            self.score,
         );
-        let end: String = end.chars().rev().collect();
-        let (end, _) = end.split_at(end.find("..").ok_or_eyre("process_random parsing failed")?);
 
-        let start: usize = start.parse()?;
-        let end: usize = end.parse()?;
-        let guess: usize = self.input.parse()?;
+        if hint.is_some() && !classic.is_over() {
+            self.input.clear();
+            if self.hints == HintStyle::Voiced {
+                self.chat_completion_output.clear();
+                self.round = Some(round.classic(
+                    self.mode,
+                    classic.attempts(),
+                    classic.max_attempts(),
+                    hint,
+                ));
+                self.process_request(Arc::clone(&self.backend))?;
+            }
+
+            return Ok(());
+        }
 
-        let random = self.rng.usize(start..=end);
+        self.result = None;
+        self.round =
+            Some(round.classic(self.mode, classic.attempts(), classic.max_attempts(), None));
+        self.classic = None;
+
+        self.process_request(Arc::clone(&self.backend))
+    }
+
+    /// This function plays the guess of the user in the game mode being played.
+    fn play_guess(&mut self) -> Result<()> {
+        match self.mode {
+            GameMode::OneShot => {
+                self.process_random()?;
+                self.process_request(Arc::clone(&self.backend))
+            }
+            GameMode::Classic | GameMode::Daily => self.process_classic(),
+        }
+    }
 
-        if guess == random {
-            self.result = Some(RandomResult::Correct);
-            self.score += 1;
+    /// This function starts the daily challenge of the day, picking up where the user left off if
+    /// they already made some guesses in it. If the challenge is already over, its summary is shown
+    /// instead. If the guesses made can't be told, the user is taken back to the game mode menu and
+    /// notified.
+    fn start_daily(&mut self) {
+        let daily = match Daily::load(Daily::default_path()) {
+            Ok(daily) => daily,
+            Err(err) => {
+                self.notice = Some(format!("{err:#}"));
+                self.screen = Screen::ModeMenu(GameMode::Daily);
+                return;
+            }
+        };
+        if daily.is_over() {
+            self.screen = Screen::Daily;
         } else {
-            self.result = Some(RandomResult::Incorrect);
+            let (start, end) = daily.range();
+            self.range_input = format!("{start}..={end}");
+            self.classic = Some(daily.classic());
+            self.screen = Screen::InGame(GameScreen::Game(GameItem::Input));
         }
+        self.daily = Some(daily);
+    }
 
-        Ok(())
+    /// This function returns whether the chat completion request in flight, if any, is for a hint
+    /// of the classic game mode rather than for the end of a round.
+    fn requesting_hint(&self) -> bool {
+        self.round.is_some_and(|round| round.hint().is_some())
     }
 
-    /// This function processes a chat completion request of the OpenRouter API, and retrieves the
-    /// message returned by the language model if the request doesn't error out. The output is then
-    /// stored in the application's persistent state.
-    fn process_request(&mut self) -> Result<()> {
+    /// This function starts processing a chat completion request for the round being played through
+    /// the given language model backend on a separate thread. The message returned by the language
+    /// model is later retrieved in [`App::handle_request`].
+    fn process_request(&mut self, backend: Arc<dyn ChatBackend>) -> Result<()> {
+        let round = self.round.ok_or_eyre("round not yet played")?;
+        // Once the session has spent past the cap, the phrase bank answers in place of the language
+        // model.
+        let backend = if self.spend_cap_exceeded() {
+            Arc::clone(&self.offline)
+        } else {
+            backend
+        };
         let request_body = Request::new(
             self.model.clone(),
-            self.result.ok_or_eyre("result not yet computed")?,
-        );
-        let agent = agent();
+            round,
+            self.stream,
+            self.persona().system_prompt().render(&round, &self.player),
+            self.transcript.messages(),
+            self.user_prompt.render(&round, &self.player),
+        )
+        .with_persona(self.persona().id().to_owned())
+        .with_fallbacks(self.fallback_models.clone(), backend.routes_fallbacks())
+        .with_sampling(self.sampling);
 
-        loop {
-            match agent
-                .post("https://openrouter.ai/api/v1/chat/completions")
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .send_json(&request_body)
-            {
-                Ok(response) => {
-                    let response: ChatCompletionResponse = response.into_body().read_json()?;
-                    let output = response
-                        .choices()
-                        .last()
-                        .ok_or_eyre("no elements in the array")?
-                        .message()
-                        .content()
-                        .clone();
-
-                    if output.is_empty() {
-                        continue;
-                    }
-                    self.chat_completion_output = output;
-                    break Ok(());
-                }
-                Err(err) => break Err(err.into()),
-            }
+        self.attempt = 0;
+        self.model.clone_into(&mut self.reply_model);
+        self.request_worker = Some(Worker::spawn(
+            backend,
+            request_body,
+            self.retry_policy,
+            // The jitter is drawn apart from the RNG of the game, so that the numbers drawn don't
+            // depend on how many requests were made.
+            Rng::new(),
+        ));
+
+        Ok(())
+    }
+
+    /// This function returns whether the replies of the session have cost more than the spend cap,
+    /// if any. Replies whose cost is unknown are left out, so the cap is checked against a lower
+    /// bound of what the session cost.
+    pub(crate) fn spend_cap_exceeded(&self) -> bool {
+        self.spend_cap.is_some_and(|spend_cap| {
+            self.session_usage
+                .cost()
+                .is_some_and(|cost| cost > spend_cap)
+        })
+    }
+
+    /// This function accounts for the usage of a request answered by the given model, both in the
+    /// round and in the session. If the server didn't report what the request cost, it's estimated
+    /// out of the price of the model, if known.
+    fn add_usage(&mut self, usage: Usage, model: &str) {
+        let prices = self
+            .catalog
+            .iter()
+            .find(|info| info.id() == model)
+            .and_then(ModelInfo::prices);
+        let usage = usage.or_estimate(prices);
+
+        self.round_usage.get_or_insert_default().add(usage);
+        self.session_usage.add(usage);
+    }
+
+    /// This function dismisses the error popup and performs the chat completion request for the
+    /// round being played again, through the given language model backend.
+    fn retry_request(&mut self, backend: Arc<dyn ChatBackend>) -> Result<()> {
+        self.error = None;
+        self.chat_completion_output.clear();
+        // If part of the message had already arrived, the round has already been accounted for.
+        self.screen = if self.result.is_some() {
+            Screen::InGame(GameScreen::EndMenu(EndMenuItem::Repeat))
+        } else {
+            Screen::InGame(GameScreen::Game(GameItem::Input))
+        };
+
+        self.process_request(backend)
+    }
+
+    /// This function writes the configuration back to the configuration file, if there's any place
+    /// to keep it. The setting is still applied to the session if it can't be kept, in which case
+    /// the user is notified.
+    fn save_config(&mut self) {
+        if let Some(Err(err)) = self
+            .config_path
+            .as_deref()
+            .map(|path| self.config.save(path))
+        {
+            self.notice = Some(format!("Failed to save the settings: {err}"));
+        }
+    }
+
+    /// This function switches to the next difficulty and keeps it in the configuration file. The
+    /// range prompt is filled with the range of the new difficulty, unless the user typed in a
+    /// different one or a default range is configured.
+    fn switch_difficulty(&mut self) {
+        let difficulty = self.difficulty.next();
+        if self.config.default_range().is_none()
+            && (self.range_input.is_empty() || self.range_input == self.difficulty.range())
+        {
+            difficulty.range().clone_into(&mut self.range_input);
+        }
+
+        self.difficulty = difficulty;
+        self.config.set_difficulty(difficulty);
+        self.save_config();
+    }
+
+    /// This function changes the sampling parameters with the given function, and keeps them in the
+    /// configuration file.
+    fn change_sampling(&mut self, change: impl FnOnce(&mut Sampling)) {
+        change(&mut self.sampling);
+        self.config.set_sampling(self.sampling);
+        self.save_config();
+    }
+
+    /// This function returns the persona the language model speaks as.
+    pub(crate) const fn persona(&self) -> &Persona {
+        self.personas.current()
+    }
+
+    /// This function switches to the next persona, wrapping around after the last one, and keeps it
+    /// in the configuration file.
+    fn switch_persona(&mut self) {
+        self.personas.switch();
+        self.config.set_persona(self.persona().id().to_owned());
+        self.save_config();
+    }
+
+    /// This function returns whether there is a chat completion request currently in flight.
+    pub(crate) const fn processing_request(&self) -> bool {
+        self.request_worker.is_some()
+    }
+
+    /// This function cancels the chat completion request currently in flight, if any. If no piece of
+    /// the message has arrived yet, the result of the round is discarded so that the user may try
+    /// again from the input prompts. Otherwise, the message is just left as it is, and the round is
+    /// kept in the history with the part of the message that did arrive. A hint is never kept, as
+    /// it's not a round of its own.
+    fn cancel_request(&mut self) {
+        self.request_worker = None;
+        if self.result.is_none() || self.requesting_hint() {
+            self.round = None;
+            return;
+        }
+
+        self.record_round();
+    }
+
+    /// This function keeps the round that has just been played in the history, along with the
+    /// message of the language model. This is done only once per round, as the round is taken out
+    /// of the application's state in the process. The game carries on if the history file can't be
+    /// written, in which case the user is notified.
+    fn record_round(&mut self) {
+        let Some(round) = self.round.take() else {
+            return;
+        };
+
+        self.transcript.push(
+            self.user_prompt.render(&round, &self.player),
+            self.chat_completion_output.clone(),
+        );
+        if let Err(err) = self.history.append(HistoryEntry::new(
+            &round,
+            self.seed,
+            self.reply_model.clone(),
+            self.chat_completion_output.clone(),
+            self.round_usage,
+        )) {
+            self.notice = Some(format!("Failed to keep the round in the history: {err}"));
         }
     }
 
@@ -179,8 +824,8 @@ impl App<'_> {
     ///
     /// # Errors
     ///
-    /// - [`std::io::Error`]
-    /// - [`ureq::Error`]
+    /// - [`std::io::Error`] if the terminal can't be drawn to or read from.
+    /// - [`crate::error::ApiError`] if the model list can't be fetched.
     pub fn run(&mut self, mut term: DefaultTerminal) -> Result<()> {
         while !self.exit {
             let _ = term.draw(|frame| frame.render_widget(&mut *self, frame.area()))?;
@@ -189,104 +834,410 @@ impl App<'_> {
         Ok(())
     }
 
-    /// This function handles the event where the program requires the chat completion request to be
-    /// processed.
-    fn handle_request(&mut self) -> Result<()> {
-        if self.processing_request {
-            self.process_random()?;
-            self.process_request()?;
-            self.screen = Screen::InGame(GameScreen::EndMenu(EndMenuItem::Repeat));
-            self.processing_request = false;
-        }
-
-        Ok(())
+    /// This function runs the command given in the command-line instead of the interactive game,
+    /// printing its outcome, or returns [`None`] if no command was given. Only what the command
+    /// requires is built, so that neither the daily challenge nor the list of models take an API
+    /// key.
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] if the range or the guess given are not valid, or if the
+    ///   application can't be built as told in [`App::new`].
+    /// - [`crate::error::ApiError`] if the reply or the list of models can't be fetched.
+    /// - [`std::io::Error`] if the round played can't be kept in the history file.
+    /// - [`serde_json::Error`] if the outcome can't be printed as JSON.
+    pub fn run_command(cli: &Cli) -> Option<Result<()>> {
+        cli.command().map(|command| headless::run(cli, command))
     }
 
-    /// This function handles updates to the model menu viewport. It gets issued a command to update
-    /// in either one of of the upward or downward directions, and makes the corresponding changes
-    /// to the persistent state related to this part of the application.
-    fn handle_model_menu_updates(&mut self, direction: ModelMenuDirection) -> Result<()> {
-        match direction {
-            ModelMenuDirection::Down => {
-                let mut first_model_after_view = String::new();
-
-                for (idx, model) in self.models.iter().enumerate() {
-                    if *model == self.model_view_selected
-                        && model
-                            != self
-                                .models
-                                .last()
-                                .ok_or_eyre("empty vector when browsing down models")?
-                    {
-                        if self.model_view_selected
-                            == self
-                                .models_view
-                                .last()
-                                .ok_or_eyre("empty vector when browsing down models")?
-                                .to_string()
-                        {
-                            first_model_after_view.clone_from(
-                                self.models
-                                    .get(idx + 1)
-                                    .ok_or_eyre("item not found when browsing down models")?,
-                            );
+    /// This function handles the event where the chat completion request being processed in the
+    /// background reports back. As soon as the message of the language model, or the first piece of
+    /// it when streaming, is available, the round is accounted for and the end menu is displayed. If
+    /// the request fails, the error popup is displayed instead.
+    fn handle_request(&mut self) {
+        while let Some(message) = self.request_worker.as_ref().and_then(Worker::poll) {
+            match message {
+                WorkerMessage::Attempt(attempt) => self.attempt = attempt,
+                WorkerMessage::Fallback(model) => self.reply_model = model,
+                // Hints are shown right below the input prompts, and they're not rounds of their own.
+                WorkerMessage::Delta(delta) if self.requesting_hint() => {
+                    self.chat_completion_output.push_str(&delta);
+                }
+                WorkerMessage::Done(outcome) if self.requesting_hint() => {
+                    self.request_worker = None;
+                    if let Ok(reply) = &outcome {
+                        if let Some(usage) = reply.usage() {
+                            self.add_usage(usage, reply.model());
                         }
-
-                        self.model_view_selected.clone_from(
-                            self.models
-                                .get(idx + 1)
-                                .ok_or_eyre("item not found when browsing down models")?,
+                    }
+                    // The plain hint is still there to fall back to if the request failed.
+                    self.chat_completion_output =
+                        outcome.map(Reply::into_content).unwrap_or_default();
+                    if let Some(round) = self.round.take() {
+                        self.transcript.push(
+                            self.user_prompt.render(&round, &self.player),
+                            self.chat_completion_output.clone(),
                         );
-                        break;
                     }
                 }
-
-                if !first_model_after_view.is_empty()
-                    && self.model_view_selected == first_model_after_view
-                {
-                    self.model_view_offset += 1;
+                WorkerMessage::Delta(delta) => {
+                    self.show_result();
+                    self.chat_completion_output.push_str(&delta);
+                }
+                WorkerMessage::Done(Ok(reply)) => {
+                    self.request_worker = None;
+                    self.show_result();
+                    reply.model().clone_into(&mut self.reply_model);
+                    if let Some(usage) = reply.usage() {
+                        self.add_usage(usage, reply.model());
+                    }
+                    self.chat_completion_output = reply.into_content();
+                    self.record_round();
+                }
+                WorkerMessage::Done(Err(err)) => {
+                    self.request_worker = None;
+                    self.error = Some(err);
+                    self.screen = Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Retry));
                 }
             }
-            ModelMenuDirection::Up => {
-                let mut first_model_before_view = String::new();
+        }
+    }
 
-                for (idx, model) in self.models.iter().enumerate() {
-                    if *model == self.model_view_selected
-                        && model
-                            != self
-                                .models
-                                .first()
-                                .ok_or_eyre("empty vector when browsing up models")?
-                    {
-                        if self.model_view_selected
-                            == self
-                                .models_view
-                                .first()
-                                .ok_or_eyre("empty vect when browsing up models")?
-                                .to_string()
-                        {
-                            first_model_before_view.clone_from(
-                                self.models
-                                    .get(idx - 1)
-                                    .ok_or_eyre("item not found while browsing up models")?,
-                            );
-                        }
+    /// This function accounts for the round that has just been played and switches to the end menu,
+    /// unless that has already been done for the current round.
+    fn show_result(&mut self) {
+        if self.result.is_none() {
+            if let Some(round) = self.round {
+                self.result = Some(round.result());
+                self.streak = round.streak();
+                self.score += round.points();
+            }
+            self.chat_completion_output.clear();
+            self.screen = Screen::InGame(GameScreen::EndMenu(EndMenuItem::Repeat));
+        }
+    }
 
-                        self.model_view_selected.clone_from(
-                            self.models
-                                .get(idx - 1)
-                                .ok_or_eyre("item not found while browsing up models")?,
-                        );
-                        break;
-                    }
+    /// This function enters the model menu from the given screen, fetching the list of models the
+    /// backend makes available. If that fails, the user stays on the current screen; the error popup
+    /// is kept on display with the new error, and the options menu shows it as a notice.
+    fn enter_model_menu(&mut self, origin: MenuOrigin) -> Result<()> {
+        let models = match self.model_cache.load() {
+            Some(cached) => {
+                if !self.model_cache.is_fresh(&cached) && self.model_refresh.is_none() {
+                    self.refresh_models();
                 }
+                Ok(cached.into_models())
+            }
+            None => self.model_cache.models(&*self.backend),
+        };
+        let models = match (models, origin) {
+            (Ok(models), _) => models,
+            (Err(err), MenuOrigin::ErrorPopup) => {
+                self.error = Some(err);
+                return Ok(());
+            }
+            (Err(err), MenuOrigin::OptionsMenu) => {
+                self.notice = Some(format!("Failed to fetch the list of models: {err}"));
+                return Ok(());
+            }
+        };
+
+        if models.is_empty() {
+            self.notice = Some("No models available".to_owned());
+            return Ok(());
+        }
+
+        self.screen = Screen::ModelMenu(origin);
+        self.catalog = models;
+        self.models.clear();
+        self.model_query = None;
+        self.filter_models();
+
+        Ok(())
+    }
+
+    /// This function starts fetching the catalog of models in the background. The catalog is
+    /// later updated in [`App::handle_model_refresh`].
+    fn refresh_models(&mut self) {
+        self.model_refresh_error = None;
+        self.model_refresh = Some(ModelRefresh::spawn(Arc::clone(&self.backend)));
+    }
+
+    /// This function handles the event where the catalog of models being fetched in the background
+    /// arrives, in which case it's cached and listed in the model menu. If fetching it failed, the
+    /// catalog already listed is kept.
+    fn handle_model_refresh(&mut self) {
+        let Some(outcome) = self.model_refresh.as_ref().and_then(ModelRefresh::poll) else {
+            return;
+        };
+        self.model_refresh = None;
+
+        match outcome {
+            Ok(models) if !models.is_empty() => {
+                // The catalog is still of use if it can't be cached.
+                drop(self.model_cache.save(&models));
+                self.catalog = models;
+                self.filter_models();
+            }
+            Ok(_) => {}
+            Err(err) => self.model_refresh_error = Some(err),
+        }
+    }
+
+    /// This function lists the models in the catalog that make it through the filters in the model
+    /// menu and match the query to search it, if any, from the closest match to the farthest. Unless
+    /// the menu is being searched, the favorite and recently used models among them are pinned
+    /// ahead of the rest. The selected model is kept selected and scrolled to if it's still listed,
+    /// and the first model listed is selected otherwise.
+    fn filter_models(&mut self) {
+        let selected = self.selected_model().map(|model| model.id().to_owned());
+        let query = self.model_query.as_deref().unwrap_or_default();
+        let models = Self::matching_models(&self.catalog, self.model_filter, query);
+
+        let pinned = |ids: &mut dyn Iterator<Item = &String>| -> Vec<ModelInfo> {
+            ids.filter_map(|id| models.iter().find(|model| model.id() == id))
+                .map(|&model| model.clone())
+                .collect()
+        };
+        let (favorites, recent) = if query.is_empty() {
+            (
+                pinned(&mut self.favorite_models.iter()),
+                pinned(
+                    &mut self
+                        .recent_models
+                        .iter()
+                        .filter(|id| !self.favorite_models.contains(id)),
+                ),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        self.pinned_models = (favorites.len(), recent.len());
+        self.models = favorites
+            .into_iter()
+            .chain(recent)
+            .chain(models.into_iter().cloned())
+            .collect();
+
+        // A model may be listed in more than one section, in which case the selection is left in
+        // the one it was in if possible.
+        self.model_view_selected = selected
+            .and_then(|selected| {
+                self.selected_model()
+                    .filter(|model| model.id() == selected)
+                    .map(|_| self.model_view_selected)
+                    .or_else(|| self.models.iter().position(|model| model.id() == selected))
+            })
+            .unwrap_or(0);
+        self.model_view_offset = 0;
+        self.scroll_to_selected();
+    }
+
+    /// This function returns the models in the given catalog that make it through the given filters
+    /// and match the given query, from the closest match to the farthest.
+    fn matching_models<'catalog>(
+        catalog: &'catalog [ModelInfo],
+        filter: ModelFilter,
+        query: &str,
+    ) -> Vec<&'catalog ModelInfo> {
+        let mut matches: Vec<_> = catalog
+            .iter()
+            .filter(|model| filter.allows(model))
+            .filter_map(|model| fuzzy_match(query, model.id()).map(|found| (found.score(), model)))
+            .collect();
+        matches.sort_by_key(|&(score, _)| Reverse(score));
+
+        matches.into_iter().map(|(_, model)| model).collect()
+    }
+
+    /// This function changes the filters applied to the list of models in the model menu with the
+    /// given function, and keeps them in the configuration file.
+    fn change_model_filter(&mut self, change: impl FnOnce(&mut ModelFilter)) {
+        change(&mut self.model_filter);
+        self.filter_models();
+        self.config.set_model_filter(self.model_filter);
+        self.save_config();
+    }
+
+    /// This function stars the model selected in the model menu, or unstars it if it was starred
+    /// already, and keeps the starred models in the configuration file.
+    fn toggle_favorite_model(&mut self) {
+        let Some(id) = self.selected_model().map(|model| model.id().to_owned()) else {
+            return;
+        };
+
+        match self
+            .favorite_models
+            .iter()
+            .position(|favorite| *favorite == id)
+        {
+            Some(position) => {
+                let _ = self.favorite_models.remove(position);
+            }
+            None => self.favorite_models.push(id),
+        }
+        self.filter_models();
+        self.config
+            .set_favorite_models(self.favorite_models.clone());
+        self.save_config();
+    }
+
+    /// This function returns the details of the model selected in the model menu, if any.
+    pub(crate) fn selected_model(&self) -> Option<&ModelInfo> {
+        self.models.get(self.model_view_selected)
+    }
+
+    /// This function handles updates to the model menu viewport. It gets issued a command to move
+    /// the selection in some direction, and scrolls the viewport so that the selection stays in
+    /// sight.
+    fn handle_model_menu_updates(&mut self, direction: ModelMenuDirection) {
+        let last = self.models.len().saturating_sub(1);
+        let page = self.model_view_height.max(1);
+
+        self.model_view_selected = match direction {
+            ModelMenuDirection::Up => self.model_view_selected.saturating_sub(1),
+            ModelMenuDirection::Down => self.model_view_selected.saturating_add(1).min(last),
+            ModelMenuDirection::PageUp => self.model_view_selected.saturating_sub(page),
+            ModelMenuDirection::PageDown => self.model_view_selected.saturating_add(page).min(last),
+            ModelMenuDirection::Top => 0,
+            ModelMenuDirection::Bottom => last,
+        };
+        self.scroll_to_selected();
+    }
+
+    /// This function returns the section of the model menu whose heading goes right before the
+    /// model at the given position in the list, if any. The list is only split into sections when
+    /// there are models pinned ahead of the rest.
+    pub(crate) fn model_section_start(&self, idx: usize) -> Option<ModelSection> {
+        let (favorites, recent) = self.pinned_models;
+        if favorites + recent == 0 {
+            return None;
+        }
+
+        [
+            (0, favorites, ModelSection::Favorites),
+            (favorites, recent, ModelSection::Recent),
+            (
+                favorites + recent,
+                self.models.len().saturating_sub(favorites + recent),
+                ModelSection::All,
+            ),
+        ]
+        .into_iter()
+        .find(|&(start, len, _)| start == idx && len > 0)
+        .map(|(_, _, section)| section)
+    }
+
+    /// This function returns the row of the model menu the model at the given position in the list
+    /// is drawn on, counting the headings of the sections before it.
+    fn model_row(&self, idx: usize) -> usize {
+        idx + (0..=idx)
+            .filter(|&idx| self.model_section_start(idx).is_some())
+            .count()
+    }
 
-                if !first_model_before_view.is_empty()
-                    && self.model_view_selected == first_model_before_view
-                {
-                    self.model_view_offset -= 1;
+    /// This function scrolls the model menu viewport just as much as needed for the selected model
+    /// to be in sight, along with the heading of its section if it's the first model in it.
+    pub(crate) fn scroll_to_selected(&mut self) {
+        let height = self.model_view_height.max(1);
+        let row = self.model_row(self.model_view_selected);
+        let top = row - usize::from(self.model_section_start(self.model_view_selected).is_some());
+
+        if top < self.model_view_offset {
+            self.model_view_offset = top;
+        } else if row >= self.model_view_offset + height {
+            self.model_view_offset = row + 1 - height;
+        }
+    }
+
+    /// This function returns whether the query to search the model menu is being typed in.
+    pub(crate) const fn searching_models(&self) -> bool {
+        matches!(self.screen, Screen::ModelMenu(_)) && self.model_query.is_some()
+    }
+
+    /// This function lists the models matching the query to search the model menu, with the closest
+    /// match selected.
+    fn search_models(&mut self) {
+        self.filter_models();
+        self.model_view_selected = 0;
+        self.model_view_offset = 0;
+    }
+
+    /// This function picks the model selected in the model menu, which entered from the given
+    /// screen, and keeps it in the configuration file.
+    fn select_model(&mut self, origin: MenuOrigin) -> Result<()> {
+        let Some(model) = self.selected_model() else {
+            // The filters and the search may leave no model to pick.
+            return Ok(());
+        };
+
+        self.model = model.id().to_owned();
+        // The phrase bank is only listed when playing offline, and it's no model to pick by
+        // default.
+        if self.model != OFFLINE_MODEL {
+            self.recent_models.retain(|recent| *recent != self.model);
+            self.recent_models.insert(0, self.model.clone());
+            self.recent_models.truncate(RECENT_MODELS);
+            self.filter_models();
+
+            self.config.set_model(self.model.clone());
+            self.config.set_recent_models(self.recent_models.clone());
+            self.save_config();
+        }
+        if origin == MenuOrigin::ErrorPopup {
+            self.model_query = None;
+            self.retry_request(Arc::clone(&self.backend))?;
+        }
+
+        Ok(())
+    }
+
+    /// This function holds the event handling behavior of the model menu. While the query to search
+    /// the menu is being typed in, characters are taken as part of it rather than as commands.
+    fn handle_model_menu_input(&mut self, origin: MenuOrigin, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Char(char) if self.searching_models() => {
+                if let Some(query) = &mut self.model_query {
+                    query.push(char);
+                }
+                self.search_models();
+            }
+            KeyCode::Backspace if self.searching_models() => {
+                if let Some(query) = &mut self.model_query {
+                    let _ = query.pop();
                 }
+                self.search_models();
             }
+            KeyCode::Esc if self.searching_models() => {
+                self.model_query = None;
+                self.filter_models();
+            }
+            KeyCode::Char('/') => self.model_query = Some(String::new()),
+            KeyCode::Char('r') if self.model_refresh.is_none() => self.refresh_models(),
+            KeyCode::Char('s') => self.toggle_favorite_model(),
+            KeyCode::Char('f') => self.change_model_filter(ModelFilter::toggle_free_only),
+            KeyCode::Char('p') => self.change_model_filter(ModelFilter::next_max_price),
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.handle_model_menu_updates(ModelMenuDirection::Down);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.handle_model_menu_updates(ModelMenuDirection::Up);
+            }
+            KeyCode::PageDown => self.handle_model_menu_updates(ModelMenuDirection::PageDown),
+            KeyCode::PageUp => self.handle_model_menu_updates(ModelMenuDirection::PageUp),
+            KeyCode::Char('g') | KeyCode::Home => {
+                self.handle_model_menu_updates(ModelMenuDirection::Top);
+            }
+            KeyCode::Char('G') | KeyCode::End => {
+                self.handle_model_menu_updates(ModelMenuDirection::Bottom);
+            }
+            KeyCode::Char('l') | KeyCode::Enter => self.select_model(origin)?,
+            KeyCode::Char('h') => {
+                self.model_query = None;
+                self.handle_h_input();
+            }
+            KeyCode::Char('q') => self.exit = true,
+            _ => {}
         }
 
         Ok(())
@@ -296,17 +1247,19 @@ impl App<'_> {
     /// deleting characters on the in-game input prompts.
     fn handle_textual_input(&mut self, operation: OperationType, char: Option<char>) -> Result<()> {
         match &self.screen {
+            // The range is locked while a round of the classic game mode is being played.
             Screen::InGame(GameScreen::Game(GameItem::Range)) => match operation {
-                OperationType::Addition => {
+                OperationType::Addition if self.classic.is_none() => {
                     self.range_input
                         .push(char.ok_or_eyre("no character to push")?);
                 }
-                OperationType::Deletion => {
+                OperationType::Deletion if self.classic.is_none() => {
                     let _ = self.range_input.pop();
                 }
                 OperationType::SwitchFocus => {
                     self.screen = Screen::InGame(GameScreen::Game(GameItem::Input));
                 }
+                OperationType::Addition | OperationType::Deletion => {}
             },
             Screen::InGame(GameScreen::Game(GameItem::Input)) => match operation {
                 OperationType::Addition => {
@@ -329,32 +1282,63 @@ impl App<'_> {
     /// event.
     fn handle_l_input(&mut self) -> Result<()> {
         match &self.screen {
-            Screen::MainMenu(MainMenuItem::Play) => {
+            Screen::MainMenu(MainMenuItem::Play) => self.screen = Screen::ModeMenu(self.mode),
+            Screen::ModeMenu(mode) => {
+                self.mode = *mode;
+                self.classic = None;
+                self.daily = None;
+                self.chat_completion_output.clear();
                 self.screen = Screen::InGame(GameScreen::Game(GameItem::Range));
+                if self.mode == GameMode::Daily {
+                    self.start_daily();
+                }
             }
+            Screen::MainMenu(MainMenuItem::Statistics) => self.screen = Screen::Statistics,
             Screen::MainMenu(MainMenuItem::Options) => {
                 self.screen = Screen::OptionsMenu(OptionsMenuItem::Model);
             }
             Screen::MainMenu(MainMenuItem::Exit) => self.exit = true,
             Screen::OptionsMenu(OptionsMenuItem::Model) => {
-                self.screen = Screen::ModelMenu;
-
-                self.model_view_offset = 0;
-                self.models = utils::fetch_models()?;
-                self.model_view_selected = self
-                    .models
-                    .first()
-                    .ok_or_else(|| eyre!("no models fetched"))?
-                    .to_owned();
+                self.enter_model_menu(MenuOrigin::OptionsMenu)?;
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Persona) => self.switch_persona(),
+            Screen::OptionsMenu(OptionsMenuItem::Difficulty) => self.switch_difficulty(),
+            Screen::OptionsMenu(OptionsMenuItem::Sampling) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::Temperature);
             }
             Screen::OptionsMenu(OptionsMenuItem::Return) => {
                 self.screen = Screen::MainMenu(MainMenuItem::Play);
             }
-            Screen::ModelMenu => {
-                self.model = self.model_view_selected.clone();
+            Screen::SamplingMenu(SamplingMenuItem::Temperature) => {
+                self.change_sampling(Sampling::next_temperature);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::TopP) => {
+                self.change_sampling(Sampling::next_top_p);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::MaxTokens) => {
+                self.change_sampling(Sampling::next_max_tokens);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::Seed) => {
+                self.change_sampling(Sampling::next_seed);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::Return) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Sampling);
+            }
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Retry)) => {
+                self.retry_request(Arc::clone(&self.backend))?;
+            }
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::SwitchModel)) => {
+                self.enter_model_menu(MenuOrigin::ErrorPopup)?;
+            }
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Offline)) => {
+                self.retry_request(Arc::clone(&self.offline))?;
             }
             Screen::InGame(GameScreen::EndMenu(EndMenuItem::Repeat)) => {
+                self.cancel_request();
                 self.screen = Screen::InGame(GameScreen::Game(GameItem::Range));
+                if self.mode == GameMode::Daily {
+                    self.start_daily();
+                }
             }
             Screen::InGame(GameScreen::EndMenu(EndMenuItem::Exit)) => {
                 self.exit = true;
@@ -367,102 +1351,186 @@ impl App<'_> {
 
     /// This function holds the event handling behavior corresponding to the 'k' character press
     /// event.
-    fn handle_k_input(&mut self) -> Result<()> {
+    const fn handle_k_input(&mut self) {
         match &self.screen {
             Screen::MainMenu(MainMenuItem::Exit) => {
                 self.screen = Screen::MainMenu(MainMenuItem::Options);
             }
             Screen::MainMenu(MainMenuItem::Options) => {
+                self.screen = Screen::MainMenu(MainMenuItem::Statistics);
+            }
+            Screen::ModeMenu(GameMode::Classic) => {
+                self.screen = Screen::ModeMenu(GameMode::OneShot);
+            }
+            Screen::ModeMenu(GameMode::Daily) => {
+                self.screen = Screen::ModeMenu(GameMode::Classic);
+            }
+            Screen::MainMenu(MainMenuItem::Statistics) => {
                 self.screen = Screen::MainMenu(MainMenuItem::Play);
             }
             Screen::OptionsMenu(OptionsMenuItem::Return) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Sampling);
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Sampling) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Difficulty);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::Return) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::Seed);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::Seed) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::MaxTokens);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::MaxTokens) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::TopP);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::TopP) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::Temperature);
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Difficulty) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Persona);
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Persona) => {
                 self.screen = Screen::OptionsMenu(OptionsMenuItem::Model);
             }
-            Screen::ModelMenu => {
-                self.handle_model_menu_updates(ModelMenuDirection::Up)?;
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Offline)) => {
+                self.screen = Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::SwitchModel));
+            }
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::SwitchModel)) => {
+                self.screen = Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Retry));
             }
             Screen::InGame(GameScreen::EndMenu(EndMenuItem::Exit)) => {
                 self.screen = Screen::InGame(GameScreen::EndMenu(EndMenuItem::Repeat));
             }
             _ => {}
         }
-
-        Ok(())
     }
 
     /// This function holds the event handling behavior corresponding to the 'j' character press
     /// event.
-    fn handle_j_input(&mut self) -> Result<()> {
+    const fn handle_j_input(&mut self) {
         match &self.screen {
             Screen::MainMenu(MainMenuItem::Play) => {
+                self.screen = Screen::MainMenu(MainMenuItem::Statistics);
+            }
+            Screen::ModeMenu(GameMode::OneShot) => {
+                self.screen = Screen::ModeMenu(GameMode::Classic);
+            }
+            Screen::ModeMenu(GameMode::Classic) => {
+                self.screen = Screen::ModeMenu(GameMode::Daily);
+            }
+            Screen::MainMenu(MainMenuItem::Statistics) => {
                 self.screen = Screen::MainMenu(MainMenuItem::Options);
             }
             Screen::MainMenu(MainMenuItem::Options) => {
                 self.screen = Screen::MainMenu(MainMenuItem::Exit);
             }
             Screen::OptionsMenu(OptionsMenuItem::Model) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Persona);
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Persona) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Difficulty);
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Difficulty) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Sampling);
+            }
+            Screen::OptionsMenu(OptionsMenuItem::Sampling) => {
                 self.screen = Screen::OptionsMenu(OptionsMenuItem::Return);
             }
-            Screen::ModelMenu => {
-                self.handle_model_menu_updates(ModelMenuDirection::Down)?;
+            Screen::SamplingMenu(SamplingMenuItem::Temperature) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::TopP);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::TopP) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::MaxTokens);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::MaxTokens) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::Seed);
+            }
+            Screen::SamplingMenu(SamplingMenuItem::Seed) => {
+                self.screen = Screen::SamplingMenu(SamplingMenuItem::Return);
+            }
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Retry)) => {
+                self.screen = Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::SwitchModel));
+            }
+            Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::SwitchModel)) => {
+                self.screen = Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::Offline));
             }
             Screen::InGame(GameScreen::EndMenu(EndMenuItem::Repeat)) => {
                 self.screen = Screen::InGame(GameScreen::EndMenu(EndMenuItem::Exit));
             }
             _ => {}
         }
-
-        Ok(())
     }
 
     /// This function holds the event handling behavior corresponding to the 'h' character press
     /// event.
-    fn handle_h_input(&mut self) {
-        if matches!(&self.screen, Screen::ModelMenu) {
-            self.screen = Screen::OptionsMenu(OptionsMenuItem::Model);
+    const fn handle_h_input(&mut self) {
+        match &self.screen {
+            Screen::ModelMenu(MenuOrigin::OptionsMenu) => {
+                self.screen = Screen::OptionsMenu(OptionsMenuItem::Model);
+            }
+            Screen::ModelMenu(MenuOrigin::ErrorPopup) => {
+                self.screen = Screen::InGame(GameScreen::ErrorPopup(ErrorPopupItem::SwitchModel));
+            }
+            Screen::SamplingMenu(_) => self.screen = Screen::OptionsMenu(OptionsMenuItem::Sampling),
+            Screen::Statistics => self.screen = Screen::MainMenu(MainMenuItem::Statistics),
+            Screen::ModeMenu(_) | Screen::Daily => {
+                self.screen = Screen::MainMenu(MainMenuItem::Play);
+            }
+            _ => {}
         }
     }
 
     /// This function serves mostly as an input handling mechanism, and as a means of processing the
     /// chat completion request with the OpenRouter API.
     fn handle_events(&mut self) -> Result<()> {
-        self.handle_request()?;
+        self.handle_request();
+        self.handle_model_refresh();
+        self.tick = self.tick.wrapping_add(1);
 
         if poll(Duration::from_millis(100)).is_ok_and(|value| value) {
             if let Event::Key(key) = read()? {
+                self.notice = None;
+                // The overlay can be toggled from any screen, including those taking text input.
+                if key.code == KeyCode::F(12) {
+                    self.toggle_overlay(Overlay::Debug);
+                    return Ok(());
+                }
+                if let Screen::ModelMenu(origin) = self.screen {
+                    return self.handle_model_menu_input(origin, key.code);
+                }
+
                 match key.code {
                     KeyCode::Char(ch)
                         if matches!(self.screen, Screen::InGame(GameScreen::Game(_)))
-                            && !self.processing_request =>
+                            && !self.processing_request() =>
                     {
                         self.handle_textual_input(OperationType::Addition, Some(ch))?;
                     }
                     KeyCode::Tab
                         if matches!(self.screen, Screen::InGame(GameScreen::Game(_)))
-                            && !self.processing_request =>
+                            && !self.processing_request() =>
                     {
                         self.handle_textual_input(OperationType::SwitchFocus, None)?;
                     }
                     KeyCode::Backspace
                         if matches!(self.screen, Screen::InGame(GameScreen::Game(_)))
-                            && !self.processing_request =>
+                            && !self.processing_request() =>
                     {
                         self.handle_textual_input(OperationType::Deletion, None)?;
                     }
                     KeyCode::Enter
                         if matches!(self.screen, Screen::InGame(GameScreen::Game(_)))
-                            && !self.processing_request =>
+                            && !self.processing_request() =>
                     {
-                        if self.validate_input()? {
-                            self.extra_line_help = false;
-                            self.processing_request = true;
-                        } else {
-                            self.extra_line_help = true;
+                        self.input_error = self.validate_input().err().map(|err| err.to_string());
+                        if self.input_error.is_none() {
+                            self.play_guess()?;
                         }
                     }
+                    KeyCode::Esc if self.processing_request() => self.cancel_request(),
                     KeyCode::Char('q') => self.exit = true,
-                    KeyCode::Char('j') => self.handle_j_input()?,
-                    KeyCode::Char('k') => self.handle_k_input()?,
+                    KeyCode::Char('j') => self.handle_j_input(),
+                    KeyCode::Char('k') => self.handle_k_input(),
                     KeyCode::Char('l') => self.handle_l_input()?,
                     KeyCode::Char('h') => self.handle_h_input(),
                     _ => {}
@@ -473,6 +1541,16 @@ impl App<'_> {
         Ok(())
     }
 
+    /// This function shows the given overlay on top of the screen, or hides it if it was already
+    /// shown.
+    fn toggle_overlay(&mut self, overlay: Overlay) {
+        self.overlay = if self.overlay == Some(overlay) {
+            None
+        } else {
+            Some(overlay)
+        };
+    }
+
     /// This function is a shorthand way of clearing a given area in the given buffer by rendering a
     /// special widget on that area.
     pub(crate) fn clear(area: Rect, buf: &mut Buffer) {
@@ -480,34 +1558,3 @@ impl App<'_> {
         clear.render(area, buf);
     }
 }
-
-impl Default for App<'_> {
-    fn default() -> Self {
-        let cli = Cli::parse();
-
-        Self {
-            exit: false,
-            screen: Screen::MainMenu(MainMenuItem::Play),
-            score: 0,
-            result: None,
-            range_input: String::new(),
-            input: String::new(),
-            model: cli
-                .model()
-                .cloned()
-                .unwrap_or_else(|| "qwen/qwen3-32b:free".to_owned()),
-            models: Vec::new(),
-            models_view: Vec::new(),
-            selectors_view: Vec::new(),
-            model_view_selected: String::new(),
-            model_view_offset: 0,
-            api_key: cli.api_key().clone(),
-            ranged_re: Regex::new(r"\A\d+\.\.\d+\z").expect("bad regex syntax"),
-            input_re: Regex::new(r"\A\d+\z").expect("bad regex syntax"),
-            extra_line_help: false,
-            processing_request: false,
-            rng: Rng::new(),
-            chat_completion_output: String::new(),
-        }
-    }
-}
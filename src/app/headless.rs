@@ -0,0 +1,255 @@
+//! This module contains support for the commands run from the command-line instead of the
+//! interactive game. They go through the same steps the game does, printing their outcome instead
+//! of drawing it, so that the game can be scripted.
+
+use std::{
+    io::{self, Write as _},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use color_eyre::{
+    eyre::{eyre, OptionExt as _},
+    Result,
+};
+use fastrand::Rng;
+use serde_json::json;
+
+use super::App;
+use crate::{
+    config::ModelFilter,
+    daily::{Daily, DAILY_ATTEMPTS},
+    history::HistoryEntry,
+    llm::{ChatBackend, Offline, Reply},
+    utils::{parse_number, Cli, Command, RandomResult, Round},
+    worker::WorkerMessage,
+};
+
+/// This constant holds how long to wait for between checks on the chat completion request in
+/// flight.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// This function runs the given command, given in the command-line along with the rest of the
+/// given arguments, building only as much of the application as the command requires.
+pub(super) fn run(cli: &Cli, command: &Command) -> Result<()> {
+    match command {
+        Command::Play { range, guess, json } => {
+            play(&mut App::new(cli)?, range.as_ref(), guess, *json)
+        }
+        Command::Models {
+            query,
+            free,
+            max_price,
+            json,
+        } => list_models(
+            cli,
+            query.as_deref().unwrap_or_default(),
+            ModelFilter::new(*free, *max_price),
+            *json,
+        ),
+        Command::Ask {
+            result,
+            range,
+            guess,
+            json,
+        } => ask(
+            &mut App::new(cli)?,
+            *result,
+            range.as_ref(),
+            guess.as_ref(),
+            *json,
+        ),
+        Command::Daily { json } => daily(*json),
+    }
+}
+
+/// This function plays a single round with the given range, if any, and guess, prints it along with
+/// the reply of the language model and keeps it in the history.
+fn play(app: &mut App, range: Option<&String>, guess: &str, json: bool) -> Result<()> {
+    set_input(app, range, Some(guess))?;
+    app.process_random()?;
+    let round = app.round.ok_or_eyre("round not yet played")?;
+
+    let reply = fetch_reply(app)?;
+    print_round(app, &round, &reply, json)?;
+
+    app.history.append(HistoryEntry::new(
+        &round,
+        app.seed,
+        reply.model().to_owned(),
+        reply.into_content(),
+        app.round_usage,
+    ))
+}
+
+/// This function prints the reply of the language model to a round with the given result, range
+/// and guess, if any, which is otherwise drawn from the range. The round is not played, so it's not
+/// kept in the history.
+fn ask(
+    app: &mut App,
+    result: RandomResult,
+    range: Option<&String>,
+    guess: Option<&String>,
+    json: bool,
+) -> Result<()> {
+    set_input(app, range, guess.map(String::as_str))?;
+    let (start, end) = app.parse_range()?;
+    let guess = parse_number(&app.input)?;
+
+    let secret = match result {
+        RandomResult::Correct => guess,
+        // Any number in the range but the guess, of which there's at least another one.
+        RandomResult::Incorrect => match app.rng.i128(start..end) {
+            secret if secret >= guess => secret + 1,
+            secret => secret,
+        },
+    };
+    let streak = match result {
+        RandomResult::Correct => 1,
+        RandomResult::Incorrect => 0,
+    };
+    let round = Round::new(result, guess, secret, (start, end), streak, 0);
+    app.round_usage = None;
+    app.round = Some(round);
+
+    let reply = fetch_reply(app)?;
+    print_round(app, &round, &reply, json)
+}
+
+/// This function lists the models available that make it through the given filters and match the
+/// given query, from the closest match to the farthest. The list is fetched without an API key
+/// unless one was given, and no model is checked against it.
+fn list_models(cli: &Cli, query: &str, filter: ModelFilter, json: bool) -> Result<()> {
+    let (_, config) = App::config(cli)?;
+    let offline: Arc<dyn ChatBackend> = Arc::new(Offline::new(Rng::new()));
+    let api_key = if cli.offline() {
+        None
+    } else {
+        App::api_key(cli, &config)?
+    };
+    let (backend, cache) = App::model_source(cli, &config, &offline, api_key);
+    let catalog = cache.models(&*backend)?;
+    let models = App::matching_models(&catalog, filter, query);
+
+    if json {
+        return print(&serde_json::to_string(&models)?);
+    }
+
+    let width = models
+        .iter()
+        .map(|model| model.id().len())
+        .max()
+        .unwrap_or_default();
+    print(
+        &models
+            .iter()
+            .map(|model| format!("{:width$}  {}", model.id(), model.name()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// This function prints the summary of the daily challenge of the day, which must be over so as not
+/// to give anything away.
+fn daily(json: bool) -> Result<()> {
+    let daily = Daily::load(Daily::default_path())?;
+    if !daily.is_over() {
+        return Err(eyre!(
+            "the daily challenge of {} is not over yet, {} of {DAILY_ATTEMPTS} guesses made",
+            daily.date(),
+            daily.attempts()
+        ));
+    }
+
+    if json {
+        print(&serde_json::to_string(&json!({
+            "date": daily.date(),
+            "range": daily.range(),
+            "guesses": daily.guesses(),
+            "verdicts": daily.verdicts(),
+            "max_attempts": DAILY_ATTEMPTS,
+            "won": daily.won(),
+        }))?)
+    } else {
+        print(&daily.summary())
+    }
+}
+
+/// This function fills the input of the application with the given range, if any, and the given
+/// guess, if any, which is otherwise drawn from the range once it's known to be valid.
+fn set_input(app: &mut App, range: Option<&String>, guess: Option<&str>) -> Result<()> {
+    if let Some(range) = range {
+        range.clone_into(&mut app.range_input);
+    }
+    // The lower bound of the range stands in for the guess to be drawn while checking the range.
+    let start = app
+        .range_input
+        .split_once("..")
+        .map_or("", |(start, _)| start);
+    guess.unwrap_or(start).clone_into(&mut app.input);
+
+    app.validate_input()?;
+    if guess.is_none() {
+        let (start, end) = app.parse_range()?;
+        app.input = app.rng.i128(start..=end).to_string();
+    }
+
+    Ok(())
+}
+
+/// This function performs the chat completion request for the round being played and waits for
+/// the reply of the language model, which is accounted for in the usage of the round.
+fn fetch_reply(app: &mut App) -> Result<Reply> {
+    app.process_request(Arc::clone(&app.backend))?;
+    let worker = app
+        .request_worker
+        .take()
+        .ok_or_eyre("request not yet performed")?;
+
+    loop {
+        match worker.poll() {
+            Some(WorkerMessage::Done(outcome)) => {
+                let reply = outcome?;
+                if let Some(usage) = reply.usage() {
+                    app.add_usage(usage, reply.model());
+                }
+                return Ok(reply);
+            }
+            Some(_) => {}
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
+
+/// This function prints the given round along with the given reply of the language model, either
+/// as a JSON object with the same shape as the entries of the history file or as plain text.
+fn print_round(app: &App, round: &Round, reply: &Reply, json: bool) -> Result<()> {
+    if json {
+        let entry = HistoryEntry::new(
+            round,
+            app.seed,
+            reply.model().to_owned(),
+            reply.content().to_owned(),
+            app.round_usage,
+        );
+        print(&serde_json::to_string(&entry)?)
+    } else {
+        print(&format!(
+            "{}\n\n{} ({}): {}",
+            round.summary(),
+            app.persona().name(),
+            reply.model(),
+            reply.content()
+        ))
+    }
+}
+
+/// This function prints the given text on a line of its own. The output being closed early, as
+/// when piped into a command that only reads part of it, is not an error.
+fn print(text: &str) -> Result<()> {
+    match writeln!(io::stdout().lock(), "{text}") {
+        Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err.into()),
+        _ => Ok(()),
+    }
+}
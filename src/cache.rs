@@ -0,0 +1,147 @@
+//! This module contains support for the cache of the list of models. The list is kept as JSON under
+//! the XDG cache directory along with the moment it was fetched, so that it needs not be fetched on
+//! every launch, and so that a stale list can still be used when the server can't be reached.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{eyre::WrapErr as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::xdg_path, error::ApiError, llm::ChatBackend, utils::ModelInfo};
+
+/// This constant holds the name of the cache file of the list of models.
+const CACHE_FILE: &str = "models.json";
+
+/// This structure holds the contents of the cache file.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CachedModels {
+    /// This field refers to the base URL of the server the list was fetched from, as the list of a
+    /// server is of no use with another one.
+    base_url: String,
+    /// This field refers to the moment the list was fetched, in seconds since the Unix epoch.
+    fetched_at: u64,
+    /// This field refers to the details of each of the models in the list.
+    models: Vec<ModelInfo>,
+}
+
+impl CachedModels {
+    /// This function returns how long ago the list was fetched.
+    pub(crate) fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+
+    /// This function consumes the cached list and returns the details of each of the models in it.
+    pub(crate) fn into_models(self) -> Vec<ModelInfo> {
+        self.models
+    }
+}
+
+/// This structure holds where the list of models of a server is cached, and for how long it's
+/// considered fresh.
+pub(crate) struct ModelCache {
+    /// This field refers to the path to the cache file, if there's any place to keep it.
+    path: Option<PathBuf>,
+    /// This field refers to the base URL of the server whose list is cached.
+    base_url: String,
+    /// This field refers to how long the cached list is used for before it's fetched again.
+    ttl: Duration,
+}
+
+impl ModelCache {
+    /// This function returns the path to the cache file under the XDG cache directory, which is
+    /// either `$XDG_CACHE_HOME` or `$HOME/.cache`.
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        xdg_path("XDG_CACHE_HOME", ".cache", CACHE_FILE)
+    }
+
+    /// This function builds the cache of the list of models of the server at the given base URL,
+    /// kept in the file at the given path and considered fresh for the given amount of time.
+    pub(crate) const fn new(path: Option<PathBuf>, base_url: String, ttl: Duration) -> Self {
+        Self {
+            path,
+            base_url,
+            ttl,
+        }
+    }
+
+    /// This function loads the cached list, if any. A missing or unreadable cache file, or one
+    /// holding the list of another server, is no cache at all.
+    pub(crate) fn load(&self) -> Option<CachedModels> {
+        let contents = fs::read_to_string(self.path.as_deref()?).ok()?;
+
+        serde_json::from_str(&contents)
+            .ok()
+            .filter(|cached: &CachedModels| cached.base_url == self.base_url)
+    }
+
+    /// This function returns the cached list, however stale, or an empty one if there's none.
+    pub(crate) fn cached_models(&self) -> Vec<ModelInfo> {
+        self.load()
+            .map(CachedModels::into_models)
+            .unwrap_or_default()
+    }
+
+    /// This function returns whether the given cached list is still fresh.
+    pub(crate) fn is_fresh(&self, cached: &CachedModels) -> bool {
+        cached.age() < self.ttl
+    }
+
+    /// This function writes the given list to the cache file, creating the directories leading to
+    /// it if needed.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file can't be written.
+    /// - [`serde_json::Error`] if the list can't be serialized.
+    pub(crate) fn save(&self, models: &[ModelInfo]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("failed to create cache directory {}", dir.display()))?;
+        }
+
+        let cached = CachedModels {
+            base_url: self.base_url.clone(),
+            fetched_at: now(),
+            models: models.to_vec(),
+        };
+        fs::write(path, serde_json::to_string(&cached)?)
+            .wrap_err_with(|| format!("failed to write cache file {}", path.display()))
+    }
+
+    /// This function returns the list of models of the given backend. The cached list is used while
+    /// it's fresh, and the list is fetched and cached again otherwise. If it can't be fetched, the
+    /// stale cached list is used instead, if any.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError`] if the list can't be fetched and there's no cached list to fall back to.
+    pub(crate) fn models(&self, backend: &dyn ChatBackend) -> Result<Vec<ModelInfo>, ApiError> {
+        let cached = self.load();
+        if let Some(cached) = cached.as_ref().filter(|cached| self.is_fresh(cached)) {
+            return Ok(cached.models.clone());
+        }
+
+        match backend.models() {
+            Ok(models) => {
+                // The list is still of use if it can't be cached.
+                drop(self.save(&models));
+                Ok(models)
+            }
+            Err(err) => cached.map(CachedModels::into_models).ok_or(err),
+        }
+    }
+}
+
+/// This function returns the current moment, in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,860 @@
+//! This module contains support for the configuration file of the application. The file is written
+//! in TOML and kept under the XDG config directory, and it holds the settings that would otherwise
+//! have to be passed on every launch, as well as those changed in-game, which are written back to
+//! it. Only the settings changed in-game are written back, so that the rest of a file written by
+//! hand, comments included, is left as it was.
+
+use std::{
+    env, fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use color_eyre::{
+    eyre::{eyre, WrapErr as _},
+    Result,
+};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use toml_edit::{DocumentMut, Item};
+
+use crate::{
+    template::Template,
+    utils::{ModelInfo, Number},
+};
+
+/// This constant holds the name of the directory holding the files of the application under each of
+/// the XDG base directories.
+const APP_DIR: &str = "randy-ng";
+
+/// This constant holds the name of the configuration file.
+const CONFIG_FILE: &str = "config.toml";
+
+/// This constant holds the maximum prices the model menu can be filtered by, in US dollars per
+/// million tokens, in the order they're switched through.
+const MAX_PRICES: [f64; 5] = [0.5, 1.0, 5.0, 10.0, 20.0];
+
+/// This constant holds the bounds of the temperatures the replies may be sampled with.
+const TEMPERATURE_BOUNDS: RangeInclusive<f64> = 0.0..=2.0;
+
+/// This constant holds the bounds of the nucleus sampling thresholds the replies may be sampled
+/// with.
+const TOP_P_BOUNDS: RangeInclusive<f64> = 0.0..=1.0;
+
+/// This constant holds the temperatures the sampling menu switches through, in order.
+const TEMPERATURES: [f64; 5] = [0.0, 0.3, 0.7, 1.0, 1.5];
+
+/// This constant holds the nucleus sampling thresholds the sampling menu switches through, in order.
+const TOP_PS: [f64; 3] = [0.5, 0.8, 0.95];
+
+/// This constant holds the maximum amounts of tokens the sampling menu switches through, in order.
+const MAX_TOKENS: [u32; 5] = [32, 64, 128, 256, 512];
+
+/// This constant holds the seeds the sampling menu switches through, in order.
+const SEEDS: [u64; 4] = [0, 1, 2, 3];
+
+/// This constant holds how long the cached list of models is used for by default, in seconds.
+const DEFAULT_MODEL_CACHE_TTL: u64 = 24 * 60 * 60;
+
+/// This function writes the given new setting over the given old one in a TOML document. The keys of
+/// tables are merged one by one, and values are only replaced if they changed, keeping the comments
+/// around them.
+fn merge_item(old: &mut Item, new: &Item) {
+    if let (Some(old_table), Some(new_table)) = (old.as_table_like_mut(), new.as_table_like()) {
+        let stale: Vec<String> = old_table
+            .iter()
+            .filter(|(key, _)| !new_table.contains_key(key))
+            .map(|(key, _)| key.to_owned())
+            .collect();
+        for key in stale {
+            drop(old_table.remove(&key));
+        }
+        for (key, new_item) in new_table.iter() {
+            match old_table.get_mut(key) {
+                Some(old_item) => merge_item(old_item, new_item),
+                None => drop(old_table.insert(key, new_item.clone())),
+            }
+        }
+    } else if let (Some(old_value), Some(new_value)) = (old.as_value_mut(), new.as_value()) {
+        let (mut current, mut wanted) = (old_value.clone(), new_value.clone());
+        current.decor_mut().clear();
+        wanted.decor_mut().clear();
+        if current.to_string() != wanted.to_string() {
+            let decor = old_value.decor().clone();
+            *old_value = new_value.clone();
+            *old_value.decor_mut() = decor;
+        }
+    } else {
+        *old = new.clone();
+    }
+}
+
+/// This function returns the path to the given file of the application under the XDG base
+/// directory held by the given environment variable, or under the given fallback directory relative
+/// to `$HOME` if the variable is not set. If neither of them is set, there's no such path.
+pub(crate) fn xdg_path(var: &str, fallback: &str, file: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(|home| PathBuf::from(home).join(fallback))
+        })
+        .map(|dir| dir.join(APP_DIR).join(file))
+}
+
+/// This structure holds the contents of the configuration file. Every setting is optional, so that
+/// anything left out of the file falls back to its default value.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// This field refers to the language model to use for the chat completion request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    /// This field refers to the language models to fall back to, in order, when the selected one
+    /// fails.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fallback_models: Vec<String>,
+    /// This field refers to the base URL of the OpenAI-compatible server to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_url: Option<String>,
+    /// This field refers to the path to a file holding the API key, so that the key itself needs not
+    /// be written in the configuration file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key_file: Option<PathBuf>,
+    /// This field refers to the system prompt of a persona of its own, which is picked unless
+    /// another persona is selected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_prompt: Option<Template>,
+    /// This field refers to the template of the message telling the language model about each
+    /// round, in place of the summary of the round.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_prompt: Option<Template>,
+    /// This field refers to the name the user goes by in the templates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    player: Option<String>,
+    /// This field refers to the identifier of the persona the language model speaks as.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    persona: Option<String>,
+    /// This field refers to the range the range prompt is filled with at the start of the game, in
+    /// the same format the user would input it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_range: Option<String>,
+    /// This field refers to how long the cached list of models is used for before it's fetched
+    /// again, in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model_cache_ttl: Option<u64>,
+    /// This field refers to the identifiers of the models starred in the model menu, in the order
+    /// they were starred in.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    favorite_models: Vec<String>,
+    /// This field refers to the identifiers of the models picked most recently in the model menu,
+    /// from the latest one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recent_models: Vec<String>,
+    /// This field refers to the most the session may spend on replies, in US dollars, before falling
+    /// back to the phrase bank.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    spend_cap: Option<f64>,
+    /// This field refers to the seed of the RNG the numbers of the game are drawn with, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    /// This field refers to the difficulty of the game.
+    difficulty: Difficulty,
+    /// This field refers to how the hints of the classic game mode are given.
+    hints: HintStyle,
+    /// This field refers to how much the language model remembers of earlier rounds.
+    memory: Memory,
+    /// This field refers to the colors the interface is drawn with.
+    colors: Theme,
+    /// This field refers to the filters applied to the list of models in the model menu.
+    model_filter: ModelFilter,
+    /// This field refers to the sampling parameters sent along with the chat completion request.
+    sampling: Sampling,
+    /// This field refers to the keys of the settings changed in-game, which are the only ones
+    /// written back to the file.
+    #[serde(skip)]
+    touched: Vec<&'static str>,
+}
+
+impl Config {
+    /// This function returns the path to the configuration file under the XDG config directory,
+    /// which is either `$XDG_CONFIG_HOME` or `$HOME/.config`. If neither of them is set, there's no
+    /// configuration file at all.
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        xdg_path("XDG_CONFIG_HOME", ".config", CONFIG_FILE)
+    }
+
+    /// This function loads the configuration file at the given path. A missing file is not an
+    /// error, but rather an empty configuration.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file exists but can't be read.
+    /// - [`toml::de::Error`] if the file is not a valid configuration, such as one with a template
+    ///   referring to an unknown variable.
+    /// - [`color_eyre::Report`] if any of the sampling parameters is out of its bounds.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(err)
+                    .wrap_err_with(|| format!("failed to read config file {}", path.display()))
+            }
+        };
+
+        let config: Self = toml::from_str(&contents)
+            .wrap_err_with(|| format!("invalid config file {}", path.display()))?;
+        config
+            .sampling
+            .validate()
+            .and_then(|()| {
+                config
+                    .spend_cap
+                    .map_or(Ok(()), check_spend_cap)
+                    .map_err(|err| eyre!("invalid spend_cap: {err}"))
+            })
+            .wrap_err_with(|| format!("invalid config file {}", path.display()))?;
+
+        Ok(config)
+    }
+
+    /// This function parses the given text into a spend cap, which must be a finite amount of
+    /// dollars no less than zero.
+    ///
+    /// # Errors
+    ///
+    /// - [`String`] describing why the text is not a valid spend cap.
+    pub(crate) fn parse_spend_cap(text: &str) -> Result<f64, String> {
+        let spend_cap = text.parse().map_err(|err| format!("{err}"))?;
+        check_spend_cap(spend_cap).map(|()| spend_cap)
+    }
+
+    /// This function writes the settings changed in-game to the file at the given path, creating
+    /// the directories leading to it if needed. The file is edited in place, so that the rest of it
+    /// is left as it was.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file exists but can't be read, or if it can't be written.
+    /// - [`toml_edit::TomlError`] if the file is not valid TOML.
+    /// - [`toml::ser::Error`] if the configuration can't be serialized.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let mut document = match fs::read_to_string(path) {
+            Ok(contents) => contents
+                .parse()
+                .wrap_err_with(|| format!("invalid config file {}", path.display()))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => DocumentMut::new(),
+            Err(err) => {
+                return Err(err)
+                    .wrap_err_with(|| format!("failed to read config file {}", path.display()))
+            }
+        };
+        let settings: DocumentMut = toml::to_string(self)?.parse()?;
+
+        for &key in &self.touched {
+            match (document.get_mut(key), settings.get(key)) {
+                (Some(old), Some(new)) => merge_item(old, new),
+                (None, Some(new)) => drop(document.insert(key, new.clone())),
+                (_, None) => drop(document.remove(key)),
+            }
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("failed to create config directory {}", dir.display()))?;
+        }
+        fs::write(path, document.to_string())
+            .wrap_err_with(|| format!("failed to write config file {}", path.display()))
+    }
+
+    /// This function marks the setting with the given key as changed in-game, so that it's written
+    /// back to the file.
+    fn touch(&mut self, key: &'static str) {
+        if !self.touched.contains(&key) {
+            self.touched.push(key);
+        }
+    }
+
+    /// This function returns the API key held by the file the configuration points to, if any.
+    /// Surrounding whitespace, such as a trailing newline, is not considered part of the key.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file can't be read.
+    pub(crate) fn api_key(&self) -> Result<Option<String>> {
+        self.api_key_file
+            .as_deref()
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map(|key| key.trim().to_owned())
+                    .wrap_err_with(|| format!("failed to read API key file {}", path.display()))
+            })
+            .transpose()
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@model`] field
+    /// of the structure.
+    pub(crate) const fn model(&self) -> Option<&String> {
+        self.model.as_ref()
+    }
+
+    /// This function sets the value of the [`struct@Config::field@model`] field of the structure.
+    pub(crate) fn set_model(&mut self, model: String) {
+        self.model = Some(model);
+        self.touch("model");
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Config::field@fallback_models`] field of the structure.
+    pub(crate) fn fallback_models(&self) -> &[String] {
+        &self.fallback_models
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@base_url`]
+    /// field of the structure.
+    pub(crate) const fn base_url(&self) -> Option<&String> {
+        self.base_url.as_ref()
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Config::field@system_prompt`] field of the structure.
+    pub(crate) const fn system_prompt(&self) -> Option<&Template> {
+        self.system_prompt.as_ref()
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@user_prompt`]
+    /// field of the structure.
+    pub(crate) const fn user_prompt(&self) -> Option<&Template> {
+        self.user_prompt.as_ref()
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@player`] field
+    /// of the structure.
+    pub(crate) const fn player(&self) -> Option<&String> {
+        self.player.as_ref()
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@persona`]
+    /// field of the structure.
+    pub(crate) const fn persona(&self) -> Option<&String> {
+        self.persona.as_ref()
+    }
+
+    /// This function sets the value of the [`struct@Config::field@persona`] field of the structure.
+    pub(crate) fn set_persona(&mut self, persona: String) {
+        self.persona = Some(persona);
+        self.touch("persona");
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Config::field@default_range`] field of the structure.
+    pub(crate) const fn default_range(&self) -> Option<&String> {
+        self.default_range.as_ref()
+    }
+
+    /// This function returns how long the cached list of models is used for, as set in the
+    /// [`struct@Config::field@model_cache_ttl`] field of the structure, or a day if not set.
+    pub(crate) fn model_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.model_cache_ttl.unwrap_or(DEFAULT_MODEL_CACHE_TTL))
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@spend_cap`]
+    /// field of the structure.
+    pub(crate) const fn spend_cap(&self) -> Option<f64> {
+        self.spend_cap
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@seed`] field
+    /// of the structure.
+    pub(crate) const fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Config::field@favorite_models`] field of the structure.
+    pub(crate) fn favorite_models(&self) -> &[String] {
+        &self.favorite_models
+    }
+
+    /// This function sets the value of the [`struct@Config::field@favorite_models`] field of the
+    /// structure.
+    pub(crate) fn set_favorite_models(&mut self, favorite_models: Vec<String>) {
+        self.favorite_models = favorite_models;
+        self.touch("favorite_models");
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Config::field@recent_models`] field of the structure.
+    pub(crate) fn recent_models(&self) -> &[String] {
+        &self.recent_models
+    }
+
+    /// This function sets the value of the [`struct@Config::field@recent_models`] field of the
+    /// structure.
+    pub(crate) fn set_recent_models(&mut self, recent_models: Vec<String>) {
+        self.recent_models = recent_models;
+        self.touch("recent_models");
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@difficulty`]
+    /// field of the structure.
+    pub(crate) const fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// This function sets the value of the [`struct@Config::field@difficulty`] field of the
+    /// structure.
+    pub(crate) fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        self.touch("difficulty");
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@hints`] field
+    /// of the structure.
+    pub(crate) const fn hints(&self) -> HintStyle {
+        self.hints
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@memory`] field
+    /// of the structure.
+    pub(crate) const fn memory(&self) -> Memory {
+        self.memory
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@colors`] field
+    /// of the structure.
+    pub(crate) const fn colors(&self) -> Theme {
+        self.colors
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Config::field@model_filter`] field of the structure.
+    pub(crate) const fn model_filter(&self) -> ModelFilter {
+        self.model_filter
+    }
+
+    /// This function sets the value of the [`struct@Config::field@model_filter`] field of the
+    /// structure.
+    pub(crate) fn set_model_filter(&mut self, model_filter: ModelFilter) {
+        self.model_filter = model_filter;
+        self.touch("model_filter");
+    }
+
+    /// This function returns the currently stored value in the [`struct@Config::field@sampling`]
+    /// field of the structure.
+    pub(crate) const fn sampling(&self) -> Sampling {
+        self.sampling
+    }
+
+    /// This function sets the value of the [`struct@Config::field@sampling`] field of the
+    /// structure.
+    pub(crate) fn set_sampling(&mut self, sampling: Sampling) {
+        self.sampling = sampling;
+        self.touch("sampling");
+    }
+}
+
+/// This enumeration holds information about the difficulty levels of the game. The difficulty
+/// determines the range the range prompt is filled with, unless a default range is configured, and
+/// the amount of guesses given in the classic game mode.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Difficulty {
+    /// This variant refers to the easy difficulty, with a range of ten numbers.
+    Easy,
+    /// This variant refers to the normal difficulty, with a range of a hundred numbers.
+    #[default]
+    Normal,
+    /// This variant refers to the hard difficulty, with a range of a thousand numbers.
+    Hard,
+}
+
+impl Difficulty {
+    /// This function returns the range the range prompt is filled with for the difficulty.
+    pub(crate) const fn range(self) -> &'static str {
+        match self {
+            Self::Easy => "1..=10",
+            Self::Normal => "1..=100",
+            Self::Hard => "1..=1000",
+        }
+    }
+
+    /// This function returns the amount of guesses given in the classic game mode for the
+    /// difficulty in the range with the given inclusive bounds. The hard difficulty gives just
+    /// enough guesses to always find the number by halving the range on each guess, and the easier
+    /// ones give a few more.
+    pub(crate) const fn attempts(self, (start, end): (Number, Number)) -> u32 {
+        // Halving the range until a single number is left takes as many guesses as bits it takes
+        // to write down its size.
+        let size = end.abs_diff(start).saturating_add(1);
+        let needed = u128::BITS - size.leading_zeros();
+
+        needed
+            + match self {
+                Self::Easy => 2,
+                Self::Normal => 1,
+                Self::Hard => 0,
+            }
+    }
+
+    /// This function returns the difficulty that follows, wrapping around after the hardest one.
+    pub(crate) const fn next(self) -> Self {
+        match self {
+            Self::Easy => Self::Normal,
+            Self::Normal => Self::Hard,
+            Self::Hard => Self::Easy,
+        }
+    }
+
+    /// This function serves as a means of returning the string representation of the enumeration.
+    pub(crate) const fn repr(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+        }
+    }
+}
+
+/// This enumeration holds information about how the hints of the classic game mode are given.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HintStyle {
+    /// This variant refers to the hints being given plainly, as in "Higher" or "Lower".
+    #[default]
+    Plain,
+    /// This variant refers to the hints being voiced by the language model.
+    Voiced,
+}
+
+/// This structure holds how much the language model remembers of earlier rounds in the session.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Memory {
+    /// This field refers to the amount of earlier rounds sent along with each request. The language
+    /// model remembers nothing if it's zero.
+    turns: u32,
+    /// This field refers to the estimated maximum amount of tokens the earlier rounds may take up.
+    tokens: u32,
+}
+
+impl Memory {
+    /// This function returns the currently stored value in the [`struct@Memory::field@turns`] field
+    /// of the structure.
+    pub(crate) const fn turns(self) -> u32 {
+        self.turns
+    }
+
+    /// This function returns the currently stored value in the [`struct@Memory::field@tokens`] field
+    /// of the structure.
+    pub(crate) const fn tokens(self) -> u32 {
+        self.tokens
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self {
+            turns: 0,
+            tokens: 1000,
+        }
+    }
+}
+
+/// This structure holds the colors the interface is drawn with. Each of them may be given either by
+/// name, such as `green` or `lightred`, or in hexadecimal notation, such as `#00ff00`.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Theme {
+    /// This field refers to the color of borders, titles and highlighted items.
+    accent: Color,
+    /// This field refers to the color of the text input by the user and of unhighlighted items.
+    text: Color,
+    /// This field refers to the color of errors and warnings.
+    error: Color,
+}
+
+impl Theme {
+    /// This function returns the currently stored value in the [`struct@Theme::field@accent`] field
+    /// of the structure.
+    pub(crate) const fn accent(&self) -> Color {
+        self.accent
+    }
+
+    /// This function returns the currently stored value in the [`struct@Theme::field@text`] field of
+    /// the structure.
+    pub(crate) const fn text(&self) -> Color {
+        self.text
+    }
+
+    /// This function returns the currently stored value in the [`struct@Theme::field@error`] field
+    /// of the structure.
+    pub(crate) const fn error(&self) -> Color {
+        self.error
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Green,
+            text: Color::White,
+            error: Color::Red,
+        }
+    }
+}
+
+/// This structure holds the filters applied to the list of models in the model menu, so that
+/// expensive models are kept out of sight.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ModelFilter {
+    /// This field refers to whether only the models free to use are listed.
+    free_only: bool,
+    /// This field refers to the maximum price of the prompt and of the completion of the models
+    /// listed, in US dollars per million tokens, if any. Models whose price is unknown are listed
+    /// regardless.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_price: Option<f64>,
+}
+
+impl ModelFilter {
+    /// This function builds the filters that list only the models free to use if so told, and only
+    /// those up to the given maximum price if any.
+    pub(crate) const fn new(free_only: bool, max_price: Option<f64>) -> Self {
+        Self {
+            free_only,
+            max_price,
+        }
+    }
+
+    /// This function returns whether the given model is to be listed.
+    pub(crate) fn allows(self, model: &ModelInfo) -> bool {
+        (!self.free_only || model.is_free())
+            && self.max_price.is_none_or(|max_price| {
+                model
+                    .prices()
+                    .is_none_or(|(prompt, completion)| prompt.max(completion) <= max_price)
+            })
+    }
+
+    /// This function switches whether only the models free to use are listed.
+    pub(crate) const fn toggle_free_only(&mut self) {
+        self.free_only = !self.free_only;
+    }
+
+    /// This function switches to the next maximum price, going from the lowest one to the highest
+    /// one and then to no maximum price at all.
+    pub(crate) fn next_max_price(&mut self) {
+        self.max_price = next_preset(self.max_price, &MAX_PRICES);
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@ModelFilter::field@free_only`] field of the structure.
+    pub(crate) const fn free_only(self) -> bool {
+        self.free_only
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@ModelFilter::field@max_price`] field of the structure.
+    pub(crate) const fn max_price(self) -> Option<f64> {
+        self.max_price
+    }
+}
+
+/// This structure holds the sampling parameters sent along with the chat completion request. Those
+/// left unset are left out of the request, so that the defaults of the model are used instead.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Sampling {
+    /// This field refers to how random the reply is, from 0, the most deterministic, to 2.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    /// This field refers to the share of the most likely tokens the reply is sampled from, from 0
+    /// to 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    /// This field refers to the maximum amount of tokens of the reply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    /// This field refers to the seed the reply is sampled with, so that the same request gets the
+    /// same reply, for the models that support it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+impl Sampling {
+    /// This function builds a new set of sampling parameters out of the given values.
+    pub(crate) const fn new(
+        temperature: Option<f64>,
+        top_p: Option<f64>,
+        max_tokens: Option<u32>,
+        seed: Option<u64>,
+    ) -> Self {
+        Self {
+            temperature,
+            top_p,
+            max_tokens,
+            seed,
+        }
+    }
+
+    /// This function parses the given text into a temperature, which must be within its bounds.
+    ///
+    /// # Errors
+    ///
+    /// - [`String`] describing why the text is not a valid temperature.
+    pub(crate) fn parse_temperature(text: &str) -> Result<f64, String> {
+        let temperature = text.parse().map_err(|err| format!("{err}"))?;
+        check_bounds(temperature, &TEMPERATURE_BOUNDS).map(|()| temperature)
+    }
+
+    /// This function parses the given text into a nucleus sampling threshold, which must be within
+    /// its bounds.
+    ///
+    /// # Errors
+    ///
+    /// - [`String`] describing why the text is not a valid threshold.
+    pub(crate) fn parse_top_p(text: &str) -> Result<f64, String> {
+        let top_p = text.parse().map_err(|err| format!("{err}"))?;
+        check_bounds(top_p, &TOP_P_BOUNDS).map(|()| top_p)
+    }
+
+    /// This function checks that every sampling parameter set is within its bounds.
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] telling which parameter is out of its bounds.
+    fn validate(self) -> Result<()> {
+        if let Some(temperature) = self.temperature {
+            check_bounds(temperature, &TEMPERATURE_BOUNDS)
+                .map_err(|err| eyre!("invalid temperature: {err}"))?;
+        }
+        if let Some(top_p) = self.top_p {
+            check_bounds(top_p, &TOP_P_BOUNDS).map_err(|err| eyre!("invalid top_p: {err}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// This function returns the sampling parameters, with those left unset taken from the given
+    /// ones.
+    pub(crate) const fn or(self, fallback: Self) -> Self {
+        Self {
+            temperature: match self.temperature {
+                Some(temperature) => Some(temperature),
+                None => fallback.temperature,
+            },
+            top_p: match self.top_p {
+                Some(top_p) => Some(top_p),
+                None => fallback.top_p,
+            },
+            max_tokens: match self.max_tokens {
+                Some(max_tokens) => Some(max_tokens),
+                None => fallback.max_tokens,
+            },
+            seed: match self.seed {
+                Some(seed) => Some(seed),
+                None => fallback.seed,
+            },
+        }
+    }
+
+    /// This function switches to the next temperature, going from the lowest one to the highest one
+    /// and then to the default of the model.
+    pub(crate) fn next_temperature(&mut self) {
+        self.temperature = next_preset(self.temperature, &TEMPERATURES);
+    }
+
+    /// This function switches to the next nucleus sampling threshold, going from the lowest one to
+    /// the highest one and then to the default of the model.
+    pub(crate) fn next_top_p(&mut self) {
+        self.top_p = next_preset(self.top_p, &TOP_PS);
+    }
+
+    /// This function switches to the next maximum amount of tokens, going from the lowest one to the
+    /// highest one and then to the default of the model.
+    pub(crate) fn next_max_tokens(&mut self) {
+        self.max_tokens = next_preset(self.max_tokens, &MAX_TOKENS);
+    }
+
+    /// This function switches to the next seed, going through a few of them and then to no seed at
+    /// all.
+    pub(crate) fn next_seed(&mut self) {
+        self.seed = next_preset(self.seed, &SEEDS);
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Sampling::field@temperature`] field of the structure.
+    pub(crate) const fn temperature(self) -> Option<f64> {
+        self.temperature
+    }
+
+    /// This function returns the currently stored value in the [`struct@Sampling::field@top_p`]
+    /// field of the structure.
+    pub(crate) const fn top_p(self) -> Option<f64> {
+        self.top_p
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Sampling::field@max_tokens`] field of the structure.
+    pub(crate) const fn max_tokens(self) -> Option<u32> {
+        self.max_tokens
+    }
+
+    /// This function returns the currently stored value in the [`struct@Sampling::field@seed`]
+    /// field of the structure.
+    pub(crate) const fn seed(self) -> Option<u64> {
+        self.seed
+    }
+}
+
+/// This function checks that the given value is within the given bounds, which also rules out values
+/// that are not a number at all.
+///
+/// # Errors
+///
+/// - [`String`] describing the bounds the value is out of.
+fn check_bounds(value: f64, bounds: &RangeInclusive<f64>) -> Result<(), String> {
+    if bounds.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{value} is out of bounds, expected a number from {} to {}",
+            bounds.start(),
+            bounds.end()
+        ))
+    }
+}
+
+/// This function checks that the given spend cap is a finite amount of dollars no less than zero.
+/// A negative cap would have every reply picked from the phrase bank, and one that is not a number
+/// would never be reached.
+///
+/// # Errors
+///
+/// - [`String`] describing why the spend cap is not valid.
+fn check_spend_cap(spend_cap: f64) -> Result<(), String> {
+    if spend_cap.is_finite() && spend_cap >= 0.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "{spend_cap} is not a valid spend cap, expected a number of dollars from 0 up"
+        ))
+    }
+}
+
+/// This function returns the preset following the given value among the given presets, which are
+/// sorted from the lowest to the highest. Past the highest one, or for a value past all of them,
+/// there's no value at all, and from there on the lowest one follows.
+fn next_preset<T: PartialOrd + Copy>(value: Option<T>, presets: &[T]) -> Option<T> {
+    match value {
+        Some(value) => presets.iter().copied().find(|&preset| preset > value),
+        None => presets.first().copied(),
+    }
+}
@@ -0,0 +1,228 @@
+//! This module contains support for the daily challenge, a round of the classic game mode whose
+//! range and number are drawn from the date, so that everyone gets the same challenge on the same
+//! day. The guesses made are kept in a file under the XDG data directory, so that the challenge can
+//! only be played once a day.
+
+use std::{
+    cmp::Ordering,
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{eyre::WrapErr as _, Result};
+use fastrand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::xdg_path,
+    utils::{Classic, Number},
+};
+
+/// This constant holds the name of the file the guesses made in the daily challenge are kept in.
+const DAILY_FILE: &str = "daily.json";
+
+/// This constant holds the upper bounds of the ranges the daily challenge may be played in, all of
+/// which start at one.
+const DAILY_RANGES: [Number; 4] = [100, 250, 500, 1000];
+
+/// This constant holds the amount of guesses given in the daily challenge.
+pub(crate) const DAILY_ATTEMPTS: u32 = 8;
+
+/// This constant holds the amount of seconds in a day.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// This structure holds the contents of the file the guesses made in the daily challenge are kept
+/// in.
+#[derive(Default, Serialize, Deserialize)]
+struct DailyState {
+    /// This field refers to the day of the challenge the guesses were made in, in days since the
+    /// Unix epoch.
+    day: u64,
+    /// This field refers to the guesses made in the challenge, in order.
+    guesses: Vec<Number>,
+}
+
+/// This structure holds the daily challenge of the current day, along with the guesses made in it
+/// so far.
+pub(crate) struct Daily {
+    /// This field refers to the path to the file the guesses are kept in, if there's any place to
+    /// keep it.
+    path: Option<PathBuf>,
+    /// This field refers to the day of the challenge, in days since the Unix epoch.
+    day: u64,
+    /// This field refers to the lower and upper bounds of the range of the challenge.
+    range: (Number, Number),
+    /// This field refers to the number drawn for the challenge.
+    secret: Number,
+    /// This field refers to the guesses made in the challenge so far, in order.
+    guesses: Vec<Number>,
+}
+
+impl Daily {
+    /// This function returns the path to the file the guesses are kept in under the XDG data
+    /// directory, which is either `$XDG_DATA_HOME` or `$HOME/.local/share`.
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        xdg_path("XDG_DATA_HOME", ".local/share", DAILY_FILE)
+    }
+
+    /// This function loads the challenge of the current day, in UTC, along with the guesses made in
+    /// it so far as kept in the file at the given path, if any. A missing file, or one kept for
+    /// another day, means no guesses have been made yet.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file exists but can't be read.
+    /// - [`serde_json::Error`] if the file is not a valid daily challenge file, which is not taken as
+    ///   no guesses made so as not to let the challenge be played twice.
+    pub(crate) fn load(path: Option<PathBuf>) -> Result<Self> {
+        let day = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() / SECONDS_PER_DAY);
+        let state = match path.as_deref().map(|path| (path, fs::read_to_string(path))) {
+            Some((path, Ok(contents))) => serde_json::from_str(&contents)
+                .wrap_err_with(|| format!("invalid daily challenge file {}", path.display()))?,
+            Some((path, Err(err))) if err.kind() != io::ErrorKind::NotFound => {
+                return Err(err).wrap_err_with(|| {
+                    format!("failed to read daily challenge file {}", path.display())
+                });
+            }
+            _ => DailyState::default(),
+        };
+
+        // The challenge is drawn from the day alone, so that it's the same for everyone.
+        let mut rng = Rng::with_seed(day);
+        let end = rng.choice(DAILY_RANGES).unwrap_or(DAILY_RANGES[0]);
+
+        Ok(Self {
+            path,
+            day,
+            range: (1, end),
+            secret: rng.i128(1..=end),
+            guesses: if state.day == day {
+                state.guesses
+            } else {
+                Vec::new()
+            },
+        })
+    }
+
+    /// This function accounts for the given guess and writes the guesses made so far to the file
+    /// they're kept in, creating the directories leading to it if needed.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file can't be written.
+    /// - [`serde_json::Error`] if the guesses can't be serialized.
+    pub(crate) fn guess(&mut self, guess: Number) -> Result<()> {
+        self.guesses.push(guess);
+
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("failed to create data directory {}", dir.display()))?;
+        }
+
+        let state = DailyState {
+            day: self.day,
+            guesses: self.guesses.clone(),
+        };
+        fs::write(path, serde_json::to_string(&state)?)
+            .wrap_err_with(|| format!("failed to write daily challenge file {}", path.display()))
+    }
+
+    /// This function returns the round of the classic game mode the challenge is played as, with
+    /// the guesses made so far already accounted for.
+    pub(crate) fn classic(&self) -> Classic {
+        let mut classic = Classic::new(self.secret, self.range, DAILY_ATTEMPTS);
+        for &guess in &self.guesses {
+            let _ = classic.guess(guess);
+        }
+
+        classic
+    }
+
+    /// This function returns whether the number was guessed.
+    pub(crate) fn won(&self) -> bool {
+        self.guesses.contains(&self.secret)
+    }
+
+    /// This function returns whether the challenge is over, either because the number was guessed
+    /// or because the user ran out of guesses.
+    pub(crate) fn is_over(&self) -> bool {
+        self.won() || self.attempts() >= DAILY_ATTEMPTS
+    }
+
+    /// This function returns the amount of guesses made so far.
+    pub(crate) fn attempts(&self) -> u32 {
+        self.guesses.len().try_into().unwrap_or(u32::MAX)
+    }
+
+    /// This function returns the date of the challenge, in the format YYYY-MM-DD.
+    pub(crate) fn date(&self) -> String {
+        // The days are shifted to start on the 1st of March of the year 0, so that leap days fall at
+        // the end of each year, and then split into eras of 400 years, which all have the same
+        // amount of days.
+        let days = self.day + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+        format!("{year}-{month:02}-{day:02}")
+    }
+
+    /// This function returns the verdict on each of the guesses made so far, in order; namely,
+    /// "Higher" or "Lower" for wrong guesses, and "Correct" for the right one.
+    pub(crate) fn verdicts(&self) -> Vec<&'static str> {
+        self.guesses
+            .iter()
+            .map(|guess| match guess.cmp(&self.secret) {
+                Ordering::Less => "Higher",
+                Ordering::Greater => "Lower",
+                Ordering::Equal => "Correct",
+            })
+            .collect()
+    }
+
+    /// This function returns the summary of the challenge to be shared, which tells how it went
+    /// without giving the number away.
+    pub(crate) fn summary(&self) -> String {
+        let attempts = if self.won() {
+            self.attempts().to_string()
+        } else {
+            "X".to_owned()
+        };
+
+        format!(
+            "randy-ng daily {} ({}..={}): {attempts}/{DAILY_ATTEMPTS}\n{}",
+            self.date(),
+            self.range.0,
+            self.range.1,
+            self.verdicts().join(", ")
+        )
+    }
+
+    /// This function returns the currently stored value in the [`struct@Daily::field@range`] field
+    /// of the structure.
+    pub(crate) const fn range(&self) -> (Number, Number) {
+        self.range
+    }
+
+    /// This function returns the currently stored value in the [`struct@Daily::field@guesses`]
+    /// field of the structure.
+    pub(crate) fn guesses(&self) -> &[Number] {
+        &self.guesses
+    }
+}
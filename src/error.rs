@@ -0,0 +1,220 @@
+//! This module contains the error type returned by the language model backends. It keeps enough
+//! detail about each failure for the game to tell the user what went wrong, and to decide how to
+//! recover from it without tearing down the whole application.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+/// This enumeration holds information about the ways in which a request to a language model backend
+/// may fail.
+#[derive(Debug)]
+pub(crate) enum ApiError {
+    /// This variant refers to the server answering with an unsuccessful HTTP status code. The error
+    /// object in the body of the response is kept if it could be parsed.
+    Status {
+        /// This field refers to the HTTP status code of the response.
+        code: u16,
+        /// This field refers to the error object found in the body of the response, if any.
+        body: Option<ErrorBody>,
+        /// This field refers to the time the server asked to wait for before trying again, if any.
+        retry_after: Option<Duration>,
+    },
+    /// This variant refers to the server reporting an error in the body of an otherwise successful
+    /// response, as OpenRouter does when a provider fails mid-stream.
+    Provider(ErrorBody),
+    /// This variant refers to the request not reaching the server at all, or the connection being
+    /// dropped, which is usually a sign of the network being unavailable.
+    Transport(ureq::Error),
+    /// This variant refers to the response not having the expected shape.
+    Deserialize(serde_json::Error),
+    /// This variant refers to a failure while reading the response.
+    Io(io::Error),
+    /// This variant refers to the language model repeatedly replying with an empty message.
+    EmptyReply,
+    /// This variant refers to the thread the given work was performed on stopping before reporting
+    /// back, which is a bug rather than a failure of the request itself.
+    WorkerStopped(&'static str),
+}
+
+impl ApiError {
+    /// This function returns whether the error is due to the server being unreachable, in which case
+    /// there's no point in telling the user about it if there's an offline alternative.
+    pub(crate) const fn is_network(&self) -> bool {
+        matches!(self, Self::Transport(_) | Self::Io(_))
+    }
+
+    /// This function returns whether the error is likely to be transient, as is the case with rate
+    /// limits and providers being down, so that the request is worth trying again.
+    pub(crate) const fn is_retryable(&self) -> bool {
+        let code = match self {
+            Self::Status { code, .. } => Some(*code),
+            Self::Provider(body) => body.code,
+            _ => None,
+        };
+
+        matches!(code, Some(429 | 502 | 503))
+    }
+
+    /// This function returns whether the error is due to the model itself being unavailable, as is
+    /// the case with models that don't exist and providers being down, so that the request is worth
+    /// making with another model.
+    pub(crate) const fn is_model_down(&self) -> bool {
+        let code = match self {
+            Self::Status { code, .. } => Some(*code),
+            Self::Provider(body) => body.code,
+            _ => None,
+        };
+
+        matches!(code, Some(404 | 502 | 503))
+    }
+
+    /// This function returns the time the server asked to wait for before trying again, if any.
+    pub(crate) const fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::Status { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// This function returns a short description of what the given HTTP status code means when
+    /// returned by the OpenRouter API.
+    const fn describe_status(code: u16) -> &'static str {
+        match code {
+            400 => "bad request",
+            401 => "invalid credentials",
+            402 => "insufficient credits",
+            403 => "flagged input",
+            404 => "not found",
+            408 => "timed out",
+            429 => "rate limited",
+            502 => "invalid response or model down",
+            503 => "no available providers",
+            _ => "unknown error",
+        }
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status {
+                code, body: None, ..
+            } => {
+                write!(f, "{} ({code})", Self::describe_status(*code))
+            }
+            Self::Status {
+                code,
+                body: Some(body),
+                ..
+            } => write!(f, "{} ({code}): {body}", Self::describe_status(*code)),
+            Self::Provider(body) => write!(f, "provider error: {body}"),
+            Self::Transport(err) => write!(f, "network error: {err}"),
+            Self::Deserialize(err) => write!(f, "unexpected response: {err}"),
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::EmptyReply => write!(f, "the language model kept replying with empty messages"),
+            Self::WorkerStopped(work) => write!(f, "the {work} worker stopped unexpectedly"),
+        }
+    }
+}
+
+impl Error for ApiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Status { .. } | Self::Provider(_) | Self::EmptyReply | Self::WorkerStopped(_) => {
+                None
+            }
+            Self::Transport(err) => Some(err),
+            Self::Deserialize(err) => Some(err),
+            Self::Io(err) => Some(err),
+        }
+    }
+}
+
+impl From<ureq::Error> for ApiError {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::StatusCode(code) => Self::Status {
+                code,
+                body: None,
+                retry_after: None,
+            },
+            ureq::Error::Json(err) => Self::Deserialize(err),
+            ureq::Error::Io(err) => Self::Io(err),
+            err => Self::Transport(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ApiError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Deserialize(err)
+    }
+}
+
+impl From<io::Error> for ApiError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// This structure holds information about the body of an error response from the OpenRouter API.
+#[derive(Deserialize)]
+pub(crate) struct ErrorResponse {
+    /// This field refers to the error object itself.
+    error: ErrorBody,
+}
+
+impl ErrorResponse {
+    /// This function consumes the response and returns the error object it carries.
+    pub(crate) fn into_error(self) -> ErrorBody {
+        self.error
+    }
+}
+
+/// This structure holds information about the error object returned by the OpenRouter API, either
+/// in the body of an error response or in the middle of a stream.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorBody {
+    /// This field refers to the human-readable description of the error.
+    message: String,
+    /// This field refers to the code of the error, which usually matches the HTTP status code.
+    code: Option<u16>,
+    /// This field refers to the additional details about the error, if any.
+    metadata: Option<ErrorMetadata>,
+}
+
+impl Display for ErrorBody {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(code) = self.code {
+            write!(f, " (code {code})")?;
+        }
+        if let Some(metadata) = &self.metadata {
+            if let Some(provider) = &metadata.provider_name {
+                write!(f, " [provider: {provider}]")?;
+            }
+            if !metadata.reasons.is_empty() {
+                write!(f, " [reasons: {}]", metadata.reasons.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// This structure holds information about the additional details OpenRouter attaches to some of
+/// its errors; namely, the provider that failed, and the reasons why an input was flagged.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorMetadata {
+    /// This field refers to the name of the provider that failed, for provider errors.
+    provider_name: Option<String>,
+    /// This field refers to the reasons why the input was flagged, for moderation errors.
+    #[serde(default)]
+    reasons: Vec<String>,
+}
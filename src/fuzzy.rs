@@ -0,0 +1,205 @@
+//! This module contains support for the fuzzy matching used to search the model menu. A query
+//! matches a candidate if its characters appear in the candidate in the same order, though not
+//! necessarily next to each other, and the candidates are ranked by how closely they do.
+
+/// This constant holds the score of each matched character.
+const MATCH: i64 = 16;
+
+/// This constant holds the bonus of a matched character right after the previous one.
+const CONSECUTIVE: i64 = 16;
+
+/// This constant holds the bonus of a matched character at the start of a word, such as the name of
+/// a model right after the provider.
+const BOUNDARY: i64 = 24;
+
+/// This constant holds the penalty of each character skipped between two matched ones.
+const GAP: i64 = 1;
+
+/// This constant holds the characters that separate the words in a candidate.
+const SEPARATORS: [char; 5] = ['/', '-', '_', ':', '.'];
+
+/// This structure holds information about how a query matches a candidate.
+pub(crate) struct Match {
+    /// This field refers to how closely the query matches the candidate. The higher, the closer.
+    score: i64,
+    /// This field refers to the positions of the characters of the candidate that were matched.
+    positions: Vec<usize>,
+}
+
+impl Match {
+    /// This function returns the currently stored value in the [`struct@Match::field@score`] field
+    /// of the structure.
+    pub(crate) const fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// This function returns the currently stored value in the [`struct@Match::field@positions`]
+    /// field of the structure.
+    pub(crate) fn positions(&self) -> &[usize] {
+        &self.positions
+    }
+}
+
+/// This function matches the given query against the given candidate, ignoring case. An empty
+/// query matches every candidate. Every position the first character of the query appears at is
+/// tried as the start of the match, and the closest match is kept.
+pub(crate) fn fuzzy_match(query: &str, candidate: &str) -> Option<Match> {
+    let query: Vec<char> = query
+        .chars()
+        .map(|char| char.to_ascii_lowercase())
+        .collect();
+    let candidate: Vec<char> = candidate
+        .chars()
+        .map(|char| char.to_ascii_lowercase())
+        .collect();
+
+    let Some(&first) = query.first() else {
+        return Some(Match {
+            score: 0,
+            positions: Vec::new(),
+        });
+    };
+
+    candidate
+        .iter()
+        .enumerate()
+        .filter(|&(_, &char)| char == first)
+        .filter_map(|(start, _)| match_from(&query, &candidate, start))
+        .max_by_key(|found| found.score)
+}
+
+/// This function matches the given query against the given candidate, with the first character of
+/// the query matched at the given position and each of the following ones at the earliest position
+/// they appear at afterwards.
+fn match_from(query: &[char], candidate: &[char], start: usize) -> Option<Match> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next = start;
+
+    for &char in query {
+        let position = candidate
+            .iter()
+            .skip(next)
+            .position(|&other| other == char)?
+            + next;
+
+        score += MATCH;
+        match positions.last() {
+            Some(&last) if last + 1 == position => score += CONSECUTIVE,
+            Some(&last) => score -= GAP * i64::try_from(position - last - 1).unwrap_or(i64::MAX),
+            None => {}
+        }
+        if position
+            .checked_sub(1)
+            .and_then(|previous| candidate.get(previous))
+            .is_none_or(|previous| SEPARATORS.contains(previous))
+        {
+            score += BOUNDARY;
+        }
+
+        positions.push(position);
+        next = position + 1;
+    }
+
+    Some(Match { score, positions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match;
+
+    /// This function returns the positions of the characters of the given candidate matched by the
+    /// given query, if it matches at all.
+    fn positions(query: &str, candidate: &str) -> Option<Vec<usize>> {
+        fuzzy_match(query, candidate).map(|found| found.positions().to_vec())
+    }
+
+    /// This function returns the score of the given query against the given candidate, if it
+    /// matches at all.
+    fn score(query: &str, candidate: &str) -> Option<i64> {
+        fuzzy_match(query, candidate).map(|found| found.score())
+    }
+
+    /// This test checks that the characters of the query match in order, though not necessarily
+    /// next to each other, and that the positions matched are those to highlight.
+    #[test]
+    fn subsequence() {
+        assert_eq!(
+            positions("gpt4", "openai/gpt-4o"),
+            Some(vec![7, 8, 9, 11]),
+            "the dash between gpt and 4 is skipped"
+        );
+        assert_eq!(
+            positions("oai", "openai/gpt-4o"),
+            Some(vec![0, 4, 5]),
+            "the characters in between are skipped"
+        );
+    }
+
+    /// This test checks that the case of both the query and the candidate is ignored.
+    #[test]
+    fn ignores_case() {
+        assert_eq!(
+            positions("GPT", "openai/gpt-4o"),
+            Some(vec![7, 8, 9]),
+            "an upper case query matches a lower case candidate"
+        );
+        assert_eq!(
+            positions("gpt", "OpenAI/GPT-4o"),
+            Some(vec![7, 8, 9]),
+            "a lower case query matches an upper case candidate"
+        );
+    }
+
+    /// This test checks that positions are counted in characters rather than bytes, as they're
+    /// highlighted character by character.
+    #[test]
+    fn positions_in_characters() {
+        assert_eq!(
+            positions("au", "caf\u{e9}-au"),
+            Some(vec![5, 6]),
+            "the accented letter takes up a single position"
+        );
+    }
+
+    /// This test checks that the closest match is kept, favoring consecutive characters and those
+    /// at the start of a word.
+    #[test]
+    fn closest_match() {
+        assert_eq!(
+            positions("llama", "xllama/llama-3"),
+            Some(vec![7, 8, 9, 10, 11]),
+            "the whole word is matched rather than the middle of another one"
+        );
+        assert!(
+            score("gpt", "openai/gpt-4o") > score("gpt", "google/gemmapt"),
+            "consecutive characters at the start of a word score higher"
+        );
+    }
+
+    /// This test checks that an empty query matches anything, and a query whose characters don't
+    /// all appear in order matches nothing.
+    #[test]
+    fn no_match() {
+        assert_eq!(
+            positions("", "openai/gpt-4o"),
+            Some(Vec::new()),
+            "an empty query matches every candidate"
+        );
+        assert_eq!(
+            positions("xyz", "openai/gpt-4o"),
+            None,
+            "none of the characters appear"
+        );
+        assert_eq!(
+            positions("tpg", "openai/gpt-4o"),
+            None,
+            "the characters appear in the wrong order"
+        );
+        assert_eq!(
+            positions("gpt", ""),
+            None,
+            "nothing matches an empty candidate"
+        );
+    }
+}
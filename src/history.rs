@@ -0,0 +1,289 @@
+//! This module contains support for the history of rounds played. Each round is appended as a line
+//! of JSON to a file under the XDG data directory, so that statistics can be computed across runs.
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{eyre::WrapErr as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::xdg_path,
+    utils::{GameMode, Number, RandomResult, Round, Usage},
+};
+
+/// This constant holds the name of the history file.
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// This constant holds the upper bounds of the range sizes by which the win rate is broken down.
+/// Ranges larger than the last bound fall in a bucket of their own.
+const RANGE_BUCKETS: [u128; 3] = [10, 100, 1000];
+
+/// This structure holds information about a round as kept in the history file.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    /// This field refers to the moment the round was played, in seconds since the Unix epoch.
+    timestamp: u64,
+    /// This field refers to the lower and upper bounds of the range input by the user.
+    range: (Number, Number),
+    /// This field refers to the guess of the user.
+    guess: Number,
+    /// This field refers to the number drawn in the round.
+    secret: Number,
+    /// This field refers to whether the user guessed the number correctly.
+    result: RandomResult,
+    /// This field refers to the game mode the round was played in.
+    #[serde(default)]
+    mode: GameMode,
+    /// This field refers to the amount of guesses made in the round.
+    #[serde(default = "HistoryEntry::default_attempts")]
+    attempts: u32,
+    /// This field refers to the language model the reply was requested from.
+    model: String,
+    /// This field refers to the reply of the language model.
+    reply: String,
+    /// This field refers to the amount of tokens the requests of the round took up and what they
+    /// cost, if reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+    /// This field refers to the seed of the RNG the number was drawn with, so that the session can
+    /// be played again. Rounds kept before the seed was recorded have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+impl HistoryEntry {
+    /// This function builds the history entry for the given round, played just now with the given
+    /// seed, model, reply and usage.
+    pub(crate) fn new(
+        round: &Round,
+        seed: u64,
+        model: String,
+        reply: String,
+        usage: Option<Usage>,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            range: round.range(),
+            guess: round.guess(),
+            secret: round.secret(),
+            result: round.result(),
+            mode: round.mode(),
+            attempts: round.attempts(),
+            model,
+            reply,
+            usage,
+            seed: Some(seed),
+        }
+    }
+
+    /// This function returns the amount of guesses of rounds kept before the game modes were
+    /// introduced, which were all made in a single guess.
+    const fn default_attempts() -> u32 {
+        1
+    }
+
+    /// This function returns whether the round was won.
+    const fn won(&self) -> bool {
+        matches!(self.result, RandomResult::Correct)
+    }
+
+    /// This function returns the amount of numbers in the range of the round.
+    const fn range_size(&self) -> u128 {
+        self.range.1.abs_diff(self.range.0).saturating_add(1)
+    }
+}
+
+/// This structure holds the rounds played so far, along with the path to the file they're kept in.
+pub(crate) struct History {
+    /// This field refers to the path to the history file, if there's any place to keep it.
+    path: Option<PathBuf>,
+    /// This field refers to the rounds played so far, from the oldest to the most recent.
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// This function returns the path to the history file under the XDG data directory, which is
+    /// either `$XDG_DATA_HOME` or `$HOME/.local/share`.
+    pub(crate) fn default_path() -> Option<PathBuf> {
+        xdg_path("XDG_DATA_HOME", ".local/share", HISTORY_FILE)
+    }
+
+    /// This function builds an empty history to be kept in the file at the given path, if any.
+    pub(crate) const fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            entries: Vec::new(),
+        }
+    }
+
+    /// This function loads the history kept in the file at the given path, if any. A missing file
+    /// is an empty history, and lines that can't be parsed, such as one left half-written, are
+    /// skipped.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file exists but can't be read.
+    pub(crate) fn load(path: Option<PathBuf>) -> Result<Self> {
+        let entries = match path.as_deref().map(fs::read_to_string) {
+            Some(Ok(contents)) => contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect(),
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+                return Err(err).wrap_err("failed to read history file");
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(Self { path, entries })
+    }
+
+    /// This function adds the given round to the history and appends it to the history file.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the file can't be written.
+    /// - [`serde_json::Error`] if the round can't be serialized.
+    pub(crate) fn append(&mut self, entry: HistoryEntry) -> Result<()> {
+        if let Some(path) = &self.path {
+            Self::write(path, &entry)
+                .wrap_err_with(|| format!("failed to write history file {}", path.display()))?;
+        }
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// This function appends the given round as a line to the file at the given path, creating the
+    /// file and the directories leading to it if needed.
+    fn write(path: &Path, entry: &HistoryEntry) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(line.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// This function computes the statistics shown in the statistics screen out of the rounds played
+    /// so far. The given amount of most recent rounds is kept for the sparkline.
+    pub(crate) fn statistics(&self, recent: usize) -> Statistics {
+        let mut stats = Statistics::default();
+        let mut streak = 0;
+
+        for entry in &self.entries {
+            let bucket = RANGE_BUCKETS
+                .iter()
+                .position(|&bound| entry.range_size() <= bound)
+                .unwrap_or(RANGE_BUCKETS.len());
+            if let Some((played, won)) = stats.by_range.get_mut(bucket) {
+                *played += 1;
+                *won += u32::from(entry.won());
+            }
+
+            stats.played += 1;
+            if entry.won() {
+                stats.won += 1;
+                streak += 1;
+                stats.longest_streak = stats.longest_streak.max(streak);
+            } else {
+                streak = 0;
+            }
+        }
+
+        stats.recent = self
+            .entries
+            .iter()
+            .rev()
+            .take(recent)
+            .rev()
+            .map(HistoryEntry::won)
+            .collect();
+
+        stats
+    }
+}
+
+/// This structure holds the statistics computed out of the history of rounds played.
+#[derive(Default)]
+pub(crate) struct Statistics {
+    /// This field refers to the amount of rounds played.
+    played: u32,
+    /// This field refers to the amount of rounds won.
+    won: u32,
+    /// This field refers to the longest amount of rounds won in a row.
+    longest_streak: u32,
+    /// This field refers to the amount of rounds played and won for each range size bucket, in the
+    /// same order as the bounds in [`RANGE_BUCKETS`], with the larger ranges last.
+    by_range: [(u32, u32); RANGE_BUCKETS.len() + 1],
+    /// This field refers to whether each of the most recent rounds was won, from the oldest to the
+    /// most recent.
+    recent: Vec<bool>,
+}
+
+impl Statistics {
+    /// This function returns the currently stored value in the [`struct@Statistics::field@played`]
+    /// field of the structure.
+    pub(crate) const fn played(&self) -> u32 {
+        self.played
+    }
+
+    /// This function returns the currently stored value in the [`struct@Statistics::field@won`]
+    /// field of the structure.
+    pub(crate) const fn won(&self) -> u32 {
+        self.won
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Statistics::field@longest_streak`] field of the structure.
+    pub(crate) const fn longest_streak(&self) -> u32 {
+        self.longest_streak
+    }
+
+    /// This function returns the win rate broken down by range size, as the label of each bucket
+    /// along with the amount of rounds played and won in it.
+    pub(crate) fn by_range(&self) -> Vec<(String, u32, u32)> {
+        self.by_range
+            .iter()
+            .enumerate()
+            .map(|(idx, &(played, won))| {
+                let label = match (
+                    idx.checked_sub(1).and_then(|idx| RANGE_BUCKETS.get(idx)),
+                    RANGE_BUCKETS.get(idx),
+                ) {
+                    (None, Some(high)) => format!("up to {high}"),
+                    (Some(low), Some(high)) => format!("{} to {high}", low + 1),
+                    (Some(low), None) => format!("over {low}"),
+                    (None, None) => String::new(),
+                };
+                (label, played, won)
+            })
+            .collect()
+    }
+
+    /// This function returns the currently stored value in the [`struct@Statistics::field@recent`]
+    /// field of the structure.
+    pub(crate) const fn recent(&self) -> &Vec<bool> {
+        &self.recent
+    }
+}
+
+/// This function returns the percentage the first given amount makes out of the second one, or zero
+/// if the latter is zero.
+pub(crate) fn percentage(part: u32, total: u32) -> u32 {
+    part.saturating_mul(100).checked_div(total).unwrap_or(0)
+}
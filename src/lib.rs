@@ -1,8 +1,19 @@
 //! This crate contains all the functionality of the binary crate of the same name.
 
 mod app;
+mod cache;
+mod config;
+mod daily;
+mod error;
+mod fuzzy;
+mod history;
+mod llm;
+mod persona;
+mod template;
+mod transcript;
 mod ui;
 mod utils;
+mod worker;
 
 pub use app::App;
 pub use utils::Cli;
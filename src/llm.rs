@@ -0,0 +1,167 @@
+//! This module contains the abstraction over the language model providers the game is able to talk
+//! to. The game loop only ever deals with the [`ChatBackend`] trait, so that the provider in use can
+//! be swapped without touching the rest of the application.
+
+mod offline;
+mod openrouter;
+
+use std::sync::Arc;
+
+pub(crate) use offline::{Offline, OFFLINE_MODEL};
+pub(crate) use openrouter::OpenRouter;
+
+use crate::{
+    error::ApiError,
+    utils::{ModelInfo, Request, Usage},
+};
+
+/// This structure holds the message produced by the language model, along with the model that
+/// produced it. The latter may not be the model asked for, as the server may route the request to
+/// one of the fallback models.
+pub(crate) struct Reply {
+    /// This field refers to the message produced by the language model.
+    content: String,
+    /// This field refers to the identifier of the model that produced the message.
+    model: String,
+    /// This field refers to the amount of tokens the request took up and what it cost, if reported.
+    usage: Option<Usage>,
+}
+
+impl Reply {
+    /// This function builds a new reply out of the given message, the identifier of the model that
+    /// produced it and the usage of the request, if reported.
+    pub(crate) const fn new(content: String, model: String, usage: Option<Usage>) -> Self {
+        Self {
+            content,
+            model,
+            usage,
+        }
+    }
+
+    /// This function returns the currently stored value in the [`struct@Reply::field@content`]
+    /// field of the structure.
+    pub(crate) fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// This function returns the currently stored value in the [`struct@Reply::field@model`] field
+    /// of the structure.
+    pub(crate) fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// This function returns the currently stored value in the [`struct@Reply::field@usage`] field
+    /// of the structure.
+    pub(crate) const fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    /// This function consumes the reply and returns the message produced by the language model.
+    pub(crate) fn into_content(self) -> String {
+        self.content
+    }
+}
+
+/// This trait holds the operations the game requires from a language model provider. These are
+/// performing a chat completion request, and listing the models the provider makes available. As
+/// the chat completion request is performed on a separate thread, implementors must be shareable
+/// across threads.
+pub(crate) trait ChatBackend: Send + Sync {
+    /// This function performs a chat completion request with the given request body, and returns
+    /// the message produced by the language model along with the model that produced it.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError`] if the request fails or the response can't be understood.
+    fn chat(&self, request: &Request) -> Result<Reply, ApiError>;
+
+    /// This function performs a streamed chat completion request with the given request body. Each
+    /// piece of the message is handed to the given callback as soon as it arrives, and reading stops
+    /// early if the callback returns `false`. The whole message is returned at the end, along with
+    /// the model that produced it. Backends that can't stream deliver the message as a single
+    /// piece.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError`] if the request fails or the response can't be understood.
+    fn chat_stream(
+        &self,
+        request: &Request,
+        on_delta: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Reply, ApiError> {
+        let reply = self.chat(request)?;
+        let _ = on_delta(reply.content());
+
+        Ok(reply)
+    }
+
+    /// This function fetches the details of the models currently available for use through the
+    /// provider.
+    ///
+    /// # Errors
+    ///
+    /// - [`ApiError`] if the request fails or the response can't be understood.
+    fn models(&self) -> Result<Vec<ModelInfo>, ApiError>;
+
+    /// This function returns whether the provider routes a request to the fallback models by
+    /// itself when they're sent along with it, in which case they're not tried in turn by the game.
+    fn routes_fallbacks(&self) -> bool {
+        false
+    }
+}
+
+/// This structure holds a backend along with another one to fall back to whenever a chat completion
+/// request with the former fails because the server can't be reached. Any other failure is left
+/// for the user to decide on. The list of models is always that of the former.
+pub(crate) struct Fallback {
+    /// This field refers to the backend to try first.
+    primary: Arc<dyn ChatBackend>,
+    /// This field refers to the backend to use if the first one can't be reached.
+    secondary: Arc<dyn ChatBackend>,
+}
+
+impl Fallback {
+    /// This function builds a new backend that tries the first given backend, and falls back to the
+    /// second one if the former can't be reached.
+    pub(crate) fn new(primary: Arc<dyn ChatBackend>, secondary: Arc<dyn ChatBackend>) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+impl ChatBackend for Fallback {
+    fn chat(&self, request: &Request) -> Result<Reply, ApiError> {
+        match self.primary.chat(request) {
+            Err(err) if err.is_network() => self.secondary.chat(request),
+            outcome => outcome,
+        }
+    }
+
+    fn chat_stream(
+        &self,
+        request: &Request,
+        on_delta: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Reply, ApiError> {
+        let mut delivered = false;
+        let outcome = self.primary.chat_stream(request, &mut |delta| {
+            delivered = true;
+            on_delta(delta)
+        });
+
+        // Once part of the message has been delivered, mixing in the reply of another backend would
+        // only make for a garbled message.
+        match outcome {
+            Err(err) if err.is_network() && !delivered => {
+                self.secondary.chat_stream(request, on_delta)
+            }
+            outcome => outcome,
+        }
+    }
+
+    fn models(&self) -> Result<Vec<ModelInfo>, ApiError> {
+        self.primary.models()
+    }
+
+    fn routes_fallbacks(&self) -> bool {
+        self.primary.routes_fallbacks()
+    }
+}
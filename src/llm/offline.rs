@@ -0,0 +1,259 @@
+//! This module contains the implementation of the [`ChatBackend`] trait that requires no language
+//! model at all. Its replies are picked from a built-in phrase bank and filled in with the details of
+//! the round, so that the game remains playable without an API key or a network connection.
+
+use std::sync::{Mutex, PoisonError};
+
+use fastrand::Rng;
+
+use crate::{
+    error::ApiError,
+    llm::{ChatBackend, Reply},
+    utils::{Hint, ModelInfo, RandomResult, Request, Round},
+};
+
+/// This constant holds the identifier under which the phrase bank is listed as a model.
+pub(crate) const OFFLINE_MODEL: &str = "randy-ng/offline";
+
+/// This static contains the phrases of the built-in personas, along with their identifiers. Any
+/// other persona is replied to with those of the cowboy, which come first.
+static PHRASES: [(&str, Phrases); 4] = [
+    (
+        "cowboy",
+        Phrases {
+            correct: &[
+                "Well I'll be darned, partner. {guess} it was, right on the nose.",
+                "Yeehaw! You roped {guess} clean out of {low} to {high} like a seasoned wrangler.",
+                "Now that's some fine shootin'. {guess} dead center, cowpoke.",
+                "Tip of the hat to ya. Pickin' {guess} outta that herd takes real grit.",
+                "Dang if you ain't got the luck of a prairie dog in a seed barn. {guess} it is.",
+                "Saddle up and celebrate, friend. You called {guess} true as a compass.",
+            ],
+            incorrect: &[
+                "Shucks, partner. {guess} missed {secret} by {off}. That's a country mile in these \
+                 parts.",
+                "Nope. That {guess} of yours wandered off the trail somewhere 'tween {low} and \
+                 {high}.",
+                "Hold your horses, cowpoke. {guess} ain't it, not by a long shot.",
+                "You shot at {guess} and hit nothin' but tumbleweeds. The critter was hidin' at \
+                 {secret}.",
+                "Better luck next time, greenhorn. {low} to {high} is a mighty big ranch to guess \
+                 in.",
+                "Well butter my biscuit, {guess} was wrong. Dust yourself off and ride again.",
+            ],
+            streak: &[
+                "{streak} in a row! You're hotter than a branding iron, partner.",
+                "Keep it up, cowpoke. That's {streak} straight, and {guess} was no fluke.",
+                "Folks from here to Abilene are gonna hear about your {streak}-round streak.",
+            ],
+            higher: &[
+                "Aim higher, partner. {guess} is too low to reach that saddle.",
+                "Nope, {guess} is short of the mark. Ride on up the trail a ways.",
+                "Too low, cowpoke. That number's perched higher than {guess}.",
+            ],
+            lower: &[
+                "Whoa there, {guess} overshot it. Rein it in some.",
+                "Too high, partner. Bring that lasso down below {guess}.",
+                "You rode past it. The number's hidin' lower than {guess}.",
+            ],
+        },
+    ),
+    (
+        "pirate",
+        Phrases {
+            correct: &[
+                "Arr, {guess} it be! Ye struck the treasure square on the X.",
+                "Shiver me timbers, ye plucked {guess} from {low} to {high} like a true buccaneer.",
+                "Hoist the colors, matey! {guess} was the doubloon we were after.",
+                "Blow me down, ye've the eye of a lookout. {guess} dead ahead.",
+                "A fine haul, ye scallywag. {guess} goes straight in the chest.",
+                "The sea smiles on ye today. {guess} it was, true as the North Star.",
+            ],
+            incorrect: &[
+                "Arr, {guess} missed {secret} by {off} leagues. Back to the charts with ye.",
+                "Ye dug at {guess} and found naught but sand. The booty lay at {secret}.",
+                "Walk the plank, landlubber! {guess} ain't it.",
+                "Somewhere 'twixt {low} and {high} the treasure hides, but not at {guess}.",
+                "Blimey, {guess} sank to the bottom of the briny deep. Try again, matey.",
+                "The parrot could've done better than {guess}. It was {secret}, ye bilge rat.",
+            ],
+            streak: &[
+                "{streak} in a row! The whole crew's singin' shanties about ye.",
+                "That be {streak} straight, matey. The Seven Seas bow before ye.",
+                "Yo ho ho! {streak} treasures plundered, and {guess} the finest of 'em.",
+            ],
+            higher: &[
+                "Climb the rigging, matey! The treasure lies higher than {guess}.",
+                "Too low, ye scallywag. Set yer sights above {guess}.",
+                "Arr, {guess} be too shallow. Sail higher.",
+            ],
+            lower: &[
+                "Drop anchor, matey! The treasure lies below {guess}.",
+                "Too high, ye landlubber. Lower yer sights beneath {guess}.",
+                "{guess} sailed clean over it. Bring her down a notch.",
+            ],
+        },
+    ),
+    (
+        "noir-detective",
+        Phrases {
+            correct: &[
+                "{guess}. I had a hunch you'd crack this one, kid.",
+                "The case is closed. {guess} was hiding in plain sight between {low} and {high}.",
+                "You read the clues like a pro. {guess}, on the nose.",
+                "Another night, another mystery solved. The number was {guess}, and you knew it.",
+                "Nice work, gumshoe. {guess} sang like a canary.",
+                "Rain on the window, {guess} on the slip of paper. You got it right.",
+            ],
+            incorrect: &[
+                "{guess}. Wrong suspect. The real culprit was {secret}, {off} streets over.",
+                "The trail went cold at {guess}. The number slipped out the back at {secret}.",
+                "This city eats guesses like {guess} for breakfast. It was {secret}.",
+                "Somewhere between {low} and {high} the truth was hiding. It wasn't at {guess}.",
+                "You fingered {guess}, but {guess} had an alibi. Case stays open.",
+                "Another dead end, kid. {guess} was nothing but smoke.",
+            ],
+            streak: &[
+                "{streak} cases closed in a row. The boys downtown are starting to talk.",
+                "That's {streak} straight. In this town, a streak like that gets noticed.",
+                "{guess}, and {streak} in a row. You're the best shamus this side of the river.",
+            ],
+            higher: &[
+                "The number's hiding uptown, kid. Higher than {guess}.",
+                "{guess} is too low. Follow the money up.",
+                "My gut says it's above {guess}. My gut's never wrong.",
+            ],
+            lower: &[
+                "Too high, kid. The number's laying low somewhere under {guess}.",
+                "{guess} overshot it. Check the basement.",
+                "Down the stairs, gumshoe. It's below {guess}.",
+            ],
+        },
+    ),
+    (
+        "drill-sergeant",
+        Phrases {
+            correct: &[
+                "{guess}! OUTSTANDING, RECRUIT! DON'T LET IT GO TO YOUR HEAD!",
+                "ON TARGET! {guess} OUT OF {low} TO {high}! THAT'S HOW IT'S DONE!",
+                "{guess}! I DIDN'T THINK YOU HAD IT IN YOU, MAGGOT!",
+                "BULLSEYE! {guess}! NOW DROP AND GIVE ME TWENTY ANYWAY!",
+                "{guess}! YOU MIGHT MAKE A SOLDIER YET!",
+                "CORRECT! {guess}! WIPE THAT SMILE OFF YOUR FACE!",
+            ],
+            incorrect: &[
+                "{guess}?! YOU MISSED {secret} BY {off}! MY GRANDMOTHER SHOOTS STRAIGHTER!",
+                "WRONG! {guess} IS NOT {secret}! GIVE ME FIFTY PUSH-UPS!",
+                "WHAT WAS THAT, RECRUIT?! {guess}?! THE NUMBER WAS {secret}!",
+                "{low} TO {high} AND YOU PICK {guess}?! UNACCEPTABLE!",
+                "NEGATIVE! {guess} IS A DISGRACE TO THIS PLATOON!",
+                "YOU CALL THAT A GUESS?! {guess}! BACK IN LINE!",
+            ],
+            streak: &[
+                "{streak} IN A ROW! DON'T GET COCKY, RECRUIT!",
+                "THAT'S {streak} STRAIGHT! KEEP IT UP OR YOU'RE ON LATRINE DUTY!",
+                "{guess} AND {streak} IN A ROW! THE WHOLE BASE IS WATCHING YOU!",
+            ],
+            higher: &[
+                "HIGHER, RECRUIT! {guess} IS TOO LOW!",
+                "{guess}?! AIM HIGHER, MAGGOT!",
+                "TOO LOW! GET ABOVE {guess} ON THE DOUBLE!",
+            ],
+            lower: &[
+                "LOWER, RECRUIT! {guess} IS TOO HIGH!",
+                "{guess}?! BRING IT DOWN, MAGGOT!",
+                "TOO HIGH! GET BELOW {guess} ON THE DOUBLE!",
+            ],
+        },
+    ),
+];
+
+/// This structure holds the phrases a persona replies with, one bank for each way a round may go.
+/// The placeholders `{guess}`, `{secret}`, `{off}`, `{low}`, `{high}` and `{streak}` are replaced
+/// with the details of the round.
+struct Phrases {
+    /// This field refers to the phrases used to reply to a correct guess.
+    correct: &'static [&'static str],
+    /// This field refers to the phrases used to reply to an incorrect guess.
+    incorrect: &'static [&'static str],
+    /// This field refers to the phrases used to reply to a correct guess that extends a streak of at
+    /// least two rounds.
+    streak: &'static [&'static str],
+    /// This field refers to the phrases used to hint that the number is higher than the guess, when
+    /// the user still has attempts left.
+    higher: &'static [&'static str],
+    /// This field refers to the phrases used to hint that the number is lower than the guess, when
+    /// the user still has attempts left.
+    lower: &'static [&'static str],
+}
+
+/// This structure holds the state of the phrase bank backend, which is only the RNG used to pick
+/// each reply. It's behind a lock because the chat completion request is performed from a separate
+/// thread.
+pub(crate) struct Offline {
+    /// This field refers to the RNG used to pick a phrase out of the bank.
+    rng: Mutex<Rng>,
+}
+
+impl Offline {
+    /// This function builds a new phrase bank backend that picks its replies with the given RNG.
+    pub(crate) const fn new(rng: Rng) -> Self {
+        Self {
+            rng: Mutex::new(rng),
+        }
+    }
+
+    /// This function fills in the placeholders of the given phrase with the details of the round.
+    fn render(phrase: &str, round: &Round) -> String {
+        let (low, high) = round.range();
+
+        phrase
+            .replace("{guess}", &round.guess().to_string())
+            .replace("{secret}", &round.secret().to_string())
+            .replace("{off}", &round.guess().abs_diff(round.secret()).to_string())
+            .replace("{low}", &low.to_string())
+            .replace("{high}", &high.to_string())
+            .replace("{streak}", &round.streak().to_string())
+    }
+}
+
+impl ChatBackend for Offline {
+    fn chat(&self, request: &Request) -> Result<Reply, ApiError> {
+        let round = request.round();
+        let [(_, cowboy), ..] = &PHRASES;
+        let phrases = PHRASES
+            .iter()
+            .find(|&&(id, _)| id == request.persona())
+            .map_or(cowboy, |(_, phrases)| phrases);
+        let bank = match (round.hint(), round.result()) {
+            (Some(Hint::Higher), _) => phrases.higher,
+            (Some(Hint::Lower), _) => phrases.lower,
+            (None, RandomResult::Correct) if round.streak() > 1 => phrases.streak,
+            (None, RandomResult::Correct) => phrases.correct,
+            (None, RandomResult::Incorrect) => phrases.incorrect,
+        };
+        let phrase = self
+            .rng
+            .lock()
+            // The RNG can't be left in an inconsistent state, so a poisoned lock is of no concern.
+            .unwrap_or_else(PoisonError::into_inner)
+            .choice(bank)
+            .copied()
+            .unwrap_or_default();
+
+        Ok(Reply::new(
+            Self::render(phrase, round),
+            OFFLINE_MODEL.to_owned(),
+            None,
+        ))
+    }
+
+    fn models(&self) -> Result<Vec<ModelInfo>, ApiError> {
+        Ok(vec![ModelInfo::new(
+            OFFLINE_MODEL,
+            "Offline phrase bank",
+            "Built-in phrases in the voice of the persona filled in with the details of the round, \
+for playing without a language model.",
+        )])
+    }
+}
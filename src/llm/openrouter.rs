@@ -0,0 +1,175 @@
+//! This module contains the implementation of the [`ChatBackend`] trait for the OpenRouter API. As
+//! the OpenRouter API follows the OpenAI API, the very same implementation works against any other
+//! OpenAI-compatible server given its base URL.
+
+use std::{
+    io::{BufRead as _, BufReader},
+    time::Duration,
+};
+
+use ureq::{http::Response, Agent, Body};
+
+use crate::{
+    error::{ApiError, ErrorResponse},
+    llm::{ChatBackend, Reply},
+    utils::{
+        ChatCompletionChunk, ChatCompletionResponse, ModelInfo, ModelListResponse, Request,
+        DEFAULT_BASE_URL,
+    },
+};
+
+/// This structure holds the state required to talk to the OpenRouter API; namely, the HTTP agent,
+/// the base URL of the server and the API key used to authenticate the chat completion requests.
+pub(crate) struct OpenRouter {
+    /// This field refers to the HTTP agent used to perform every request, so that connections can
+    /// be reused across rounds.
+    agent: Agent,
+    /// This field refers to the base URL against which the endpoints of the API are resolved,
+    /// without a trailing slash.
+    base_url: String,
+    /// This field refers to the API key to be used when performing the chat completion request. It
+    /// may not be set when talking to a local server that requires no authentication.
+    api_key: Option<String>,
+}
+
+impl OpenRouter {
+    /// This function builds a new backend that talks to the server at the given base URL, and
+    /// authenticates with the given API key if any.
+    pub(crate) fn new(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            // Unsuccessful status codes are handled by hand so that the error object in the body of
+            // the response can be read.
+            agent: Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .into(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            api_key,
+        }
+    }
+
+    /// This function returns the full URL of the given endpoint of the API.
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{path}", self.base_url)
+    }
+
+    /// This function sends the given request body to the chat completion endpoint, authenticating
+    /// with the API key if any, and returns the raw response.
+    fn send(&self, request: &Request) -> Result<Response<Body>, ApiError> {
+        let mut builder = self.agent.post(self.endpoint("chat/completions"));
+        if let Some(api_key) = &self.api_key {
+            builder = builder.header("Authorization", format!("Bearer {api_key}"));
+        }
+
+        Self::check(builder.send_json(request)?)
+    }
+
+    /// This function turns a response with an unsuccessful status code into an error, keeping the
+    /// error object found in its body if it can be parsed.
+    fn check(response: Response<Body>) -> Result<Response<Body>, ApiError> {
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let code = response.status().as_u16();
+        // Only the delay in seconds is supported, which is what OpenRouter sends.
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        let body = response
+            .into_body()
+            .read_json::<ErrorResponse>()
+            .ok()
+            .map(ErrorResponse::into_error);
+
+        Err(ApiError::Status {
+            code,
+            body,
+            retry_after,
+        })
+    }
+}
+
+impl ChatBackend for OpenRouter {
+    fn chat(&self, request: &Request) -> Result<Reply, ApiError> {
+        let mut response: ChatCompletionResponse = self.send(request)?.into_body().read_json()?;
+
+        if let Some(error) = response.take_error() {
+            return Err(ApiError::Provider(error));
+        }
+
+        // A response without messages is treated as an empty message, so that it's retried.
+        let content = response
+            .choices()
+            .last()
+            .map(|choice| choice.message().content().clone())
+            .unwrap_or_default();
+        let model = response
+            .model()
+            .unwrap_or_else(|| request.model())
+            .to_owned();
+
+        Ok(Reply::new(content, model, response.usage()))
+    }
+
+    fn chat_stream(
+        &self,
+        request: &Request,
+        on_delta: &mut dyn FnMut(&str) -> bool,
+    ) -> Result<Reply, ApiError> {
+        let reader = BufReader::new(self.send(request)?.into_body().into_reader());
+        let mut output = String::new();
+        let mut model = None;
+        let mut usage = None;
+
+        for line in reader.lines() {
+            let line = line?;
+            // Lines other than data fields are either blank event separators, other fields or
+            // comments, which OpenRouter sends to keep the connection alive.
+            let Some(data) = line.strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim_start();
+
+            if data == "[DONE]" {
+                break;
+            }
+
+            let chunk: ChatCompletionChunk = serde_json::from_str(data)?;
+            if model.is_none() {
+                model = chunk.model().map(ToOwned::to_owned);
+            }
+            usage = chunk.usage().or(usage);
+            if let Some(delta) = chunk.into_content()?.filter(|delta| !delta.is_empty()) {
+                output.push_str(&delta);
+                if !on_delta(&delta) {
+                    break;
+                }
+            }
+        }
+
+        Ok(Reply::new(
+            output,
+            model.unwrap_or_else(|| request.model().to_owned()),
+            usage,
+        ))
+    }
+
+    fn models(&self) -> Result<Vec<ModelInfo>, ApiError> {
+        let response: ModelListResponse =
+            Self::check(self.agent.get(self.endpoint("models")).call()?)?
+                .into_body()
+                .read_json()?;
+
+        Ok(response.into_models())
+    }
+
+    fn routes_fallbacks(&self) -> bool {
+        // Only OpenRouter itself knows about routing, so that other servers aren't sent parameters
+        // they don't know about.
+        self.base_url == DEFAULT_BASE_URL
+    }
+}
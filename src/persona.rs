@@ -0,0 +1,263 @@
+//! This module contains support for the personas the language model may speak as. A few of them
+//! are built into the program, and more of them can be defined by the user in TOML files under the
+//! `personas` directory next to the configuration file, one persona per file. When playing offline,
+//! the built-in personas reply with phrases of their own, and any other persona with those of the
+//! cowboy.
+
+use std::{
+    collections::VecDeque,
+    fs, io, mem,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr as _},
+    Result,
+};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::template::Template;
+
+/// This constant holds the name of the directory holding the personas defined by the user, which
+/// is kept next to the configuration file.
+const PERSONAS_DIR: &str = "personas";
+
+/// This constant holds the identifier of the persona used when none is selected.
+const DEFAULT_PERSONA: &str = "cowboy";
+
+/// This constant holds the identifier of the persona made out of the system prompt in the
+/// configuration file, if any.
+const CUSTOM_PERSONA: &str = "custom";
+
+/// This constant holds the part of the system prompt of the built-in personas describing the round
+/// the language model is told about, which is the same for all of them.
+const GAME_PROMPT: &str = "You will be told about a round a user just played in a number guessing \
+game. Each round comes as a summary with its result, the range the user picked, their guess and, \
+once the round is over, the number that was drawn and how far off they were, along with their \
+streak of correct guesses and their score. The result is either \"Correct\" or \"Incorrect\" once \
+the round is over, or \"Higher\" or \"Lower\" if the user got the number wrong but still has \
+attempts left, meaning the number is higher or lower than their guess, respectively. The user goes \
+by {player}.";
+
+/// This constant holds the built-in personas, as their identifier, name, the voice they answer in
+/// and their color.
+const BUILTIN: [(&str, &str, &str, Color); 4] = [
+    ("cowboy", "Cowboy", "a cowboy-like answer", Color::Green),
+    ("pirate", "Pirate", "a pirate-like answer", Color::Cyan),
+    (
+        "noir-detective",
+        "Noir detective",
+        "an answer in the voice of a hard-boiled detective from a noir film",
+        Color::Gray,
+    ),
+    (
+        "drill-sergeant",
+        "Drill sergeant",
+        "an answer in the voice of a drill sergeant shouting at a recruit",
+        Color::Yellow,
+    ),
+];
+
+/// This structure holds information about a persona the language model may speak as.
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Persona {
+    /// This field refers to the identifier of the persona, used to select it from the command-line
+    /// and the configuration file. Personas defined by the user are identified by the name of
+    /// their file, without the extension.
+    #[serde(skip)]
+    id: String,
+    /// This field refers to the name of the persona, as shown in the options menu.
+    name: String,
+    /// This field refers to the template of the system prompt sent to the language model in the chat
+    /// completion request.
+    system_prompt: Template,
+    /// This field refers to the color the replies of the persona are drawn with, if other than the
+    /// accent color of the interface.
+    color: Option<Color>,
+}
+
+impl Persona {
+    /// This function returns the built-in personas.
+    fn builtin() -> Result<Vec<Self>> {
+        BUILTIN
+            .into_iter()
+            .map(|(id, name, voice, color)| {
+                Ok(Self {
+                    id: id.to_owned(),
+                    name: name.to_owned(),
+                    system_prompt: Template::parse(&format!(
+                    "{GAME_PROMPT} Your task is to return {voice} to the user that refers to what \
+actually happened in the round, nudging them in the right direction if they still have attempts \
+left. Make it a short text. Include just your answer and nothing more. Don't include emoji or \
+otherwise non-verbal content."
+                    ))?,
+                    color: Some(color),
+                })
+            })
+            .collect()
+    }
+
+    /// This function loads the persona defined in the file at the given path.
+    fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read persona file {}", path.display()))?;
+        let mut persona: Self = toml::from_str(&contents)
+            .wrap_err_with(|| format!("invalid persona file {}", path.display()))?;
+        persona.id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(persona)
+    }
+
+    /// This function returns the path to the directory holding the personas defined by the user,
+    /// next to the configuration file at the given path.
+    pub(crate) fn dir(config_path: &Path) -> Option<PathBuf> {
+        config_path.parent().map(|dir| dir.join(PERSONAS_DIR))
+    }
+
+    /// This function returns every persona available, starting with the built-in ones. If the
+    /// configuration file holds a system prompt, it makes up a persona of its own. The personas
+    /// defined by the user in the given directory come last, in the order of their file names, and
+    /// take the place of any other persona with the same identifier.
+    ///
+    /// # Errors
+    ///
+    /// - [`std::io::Error`] if the directory exists but a file in it can't be read.
+    /// - [`toml::de::Error`] if a file is not a valid persona, such as one whose system prompt refers
+    ///   to an unknown variable.
+    pub(crate) fn library(
+        dir: Option<&Path>,
+        system_prompt: Option<&Template>,
+    ) -> Result<Vec<Self>> {
+        let mut personas = Self::builtin()?;
+        if let Some(system_prompt) = system_prompt {
+            personas.push(Self {
+                id: CUSTOM_PERSONA.to_owned(),
+                name: "Custom".to_owned(),
+                system_prompt: system_prompt.clone(),
+                color: None,
+            });
+        }
+
+        let mut paths = match dir.map(fs::read_dir) {
+            Some(Ok(entries)) => entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<_>>>()
+                .wrap_err("failed to read personas directory")?,
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+                return Err(err).wrap_err("failed to read personas directory");
+            }
+            _ => Vec::new(),
+        };
+        paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+        paths.sort();
+
+        for path in paths {
+            let persona = Self::load(&path)?;
+            match personas.iter_mut().find(|other| other.id == persona.id) {
+                Some(other) => *other = persona,
+                None => personas.push(persona),
+            }
+        }
+
+        Ok(personas)
+    }
+
+    /// This function returns the position of the persona with the given identifier among the given
+    /// personas. If no identifier is given, the persona made out of the system prompt in the
+    /// configuration file is picked if there's any, and the cowboy otherwise.
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] if there's no persona with the given identifier.
+    pub(crate) fn position(personas: &[Self], id: Option<&String>) -> Result<usize> {
+        let position = |id: &str| personas.iter().position(|persona| persona.id == id);
+
+        match id {
+            Some(id) => position(id).ok_or_else(|| {
+                eyre!(
+                    "unknown persona {id}, expected one of: {}",
+                    personas
+                        .iter()
+                        .map(|persona| persona.id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }),
+            None => Ok(position(CUSTOM_PERSONA)
+                .or_else(|| position(DEFAULT_PERSONA))
+                .unwrap_or_default()),
+        }
+    }
+
+    /// This function returns the currently stored value in the [`struct@Persona::field@id`] field of
+    /// the structure.
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// This function returns the currently stored value in the [`struct@Persona::field@name`] field
+    /// of the structure.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Persona::field@system_prompt`] field of the structure.
+    pub(crate) const fn system_prompt(&self) -> &Template {
+        &self.system_prompt
+    }
+
+    /// This function returns the currently stored value in the [`struct@Persona::field@color`] field
+    /// of the structure.
+    pub(crate) const fn color(&self) -> Option<Color> {
+        self.color
+    }
+}
+
+/// This structure holds the personas available, arranged so that the one the language model speaks
+/// as is always at hand and the rest of them follow in the order they're switched to.
+pub(crate) struct Personas {
+    /// This field refers to the persona the language model speaks as.
+    current: Persona,
+    /// This field refers to the rest of the personas, starting with the one after the current one
+    /// and wrapping around after the last one.
+    rest: VecDeque<Persona>,
+}
+
+impl Personas {
+    /// This function arranges the given personas around the one with the given identifier, which is
+    /// picked as in [`Persona::position`].
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] if there's no persona with the given identifier, or no persona at
+    ///   all.
+    pub(crate) fn select(personas: Vec<Persona>, id: Option<&String>) -> Result<Self> {
+        let position = Persona::position(&personas, id)?;
+        let mut rest = VecDeque::from(personas);
+        rest.rotate_left(position);
+        let current = rest
+            .pop_front()
+            .ok_or_else(|| eyre!("no personas available"))?;
+
+        Ok(Self { current, rest })
+    }
+
+    /// This function switches to the next persona, wrapping around after the last one.
+    pub(crate) fn switch(&mut self) {
+        if let Some(next) = self.rest.pop_front() {
+            self.rest.push_back(mem::replace(&mut self.current, next));
+        }
+    }
+
+    /// This function returns the currently stored value in the [`struct@Personas::field@current`]
+    /// field of the structure.
+    pub(crate) const fn current(&self) -> &Persona {
+        &self.current
+    }
+}
@@ -0,0 +1,304 @@
+//! This module contains support for the templates the messages sent to the language model are
+//! rendered from. A template is plain text with variables in braces, such as `{guess}`, which are
+//! replaced with the details of the round each time a request is made. Literal braces are written
+//! twice, as in `{{` and `}}`.
+
+use color_eyre::{eyre::eyre, Report, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Round;
+
+/// This constant holds the variables templates may refer to, along with their names.
+const VARIABLES: [(&str, Variable); 10] = [
+    ("result", Variable::Result),
+    ("guess", Variable::Guess),
+    ("low", Variable::Low),
+    ("high", Variable::High),
+    ("secret", Variable::Secret),
+    ("streak", Variable::Streak),
+    ("score", Variable::Score),
+    ("attempts", Variable::Attempts),
+    ("player", Variable::Player),
+    ("summary", Variable::Summary),
+];
+
+/// This enumeration holds information about the variables templates may refer to.
+#[derive(Clone, Copy)]
+enum Variable {
+    /// This variant refers to the verdict on the guess, as in "Correct", "Incorrect", "Higher" or
+    /// "Lower".
+    Result,
+    /// This variant refers to the guess of the user.
+    Guess,
+    /// This variant refers to the lower bound of the range.
+    Low,
+    /// This variant refers to the upper bound of the range.
+    High,
+    /// This variant refers to the number drawn, which is hidden while the round is not over yet.
+    Secret,
+    /// This variant refers to the streak of correct guesses of the user.
+    Streak,
+    /// This variant refers to the score of the user, including the round.
+    Score,
+    /// This variant refers to the amount of guesses made in the round.
+    Attempts,
+    /// This variant refers to the name of the user.
+    Player,
+    /// This variant refers to the summary of the round, as sent by default.
+    Summary,
+}
+
+/// This enumeration holds information about the pieces a template is made of.
+#[derive(Clone)]
+enum Part {
+    /// This variant refers to a piece of text copied as it is.
+    Text(String),
+    /// This variant refers to a variable replaced with the corresponding detail of the round.
+    Variable(Variable),
+}
+
+/// This structure holds a template out of which a message sent to the language model is rendered.
+/// It is checked for unknown variables as soon as it is loaded, rather than when it is first
+/// rendered.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Template {
+    /// This field refers to the text the template was parsed from, which is kept around so that the
+    /// template can be written back as it was.
+    source: String,
+    /// This field refers to the pieces the template is made of, in order.
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// This function parses the given text into a template.
+    ///
+    /// # Errors
+    ///
+    /// - [`color_eyre::Report`] if the text refers to an unknown variable, or if it holds a brace
+    ///   that isn't part of a variable and isn't written twice.
+    pub(crate) fn parse(source: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.char_indices().peekable();
+
+        while let Some((offset, char)) = chars.next() {
+            match char {
+                '{' if chars.next_if(|&(_, next)| next == '{').is_some() => text.push('{'),
+                '}' if chars.next_if(|&(_, next)| next == '}').is_some() => text.push('}'),
+                '{' => {
+                    let mut closed = false;
+                    let name: String = chars
+                        .by_ref()
+                        .map(|(_, char)| char)
+                        .take_while(|&char| {
+                            closed = char == '}';
+                            !closed
+                        })
+                        .collect();
+                    if !closed {
+                        return Err(eyre!(
+                            "unclosed brace at {}, write {{{{ for a literal brace",
+                            location(source, offset)
+                        ));
+                    }
+                    let variable = VARIABLES
+                        .iter()
+                        .find(|&&(known, _)| known == name)
+                        .map(|&(_, variable)| variable)
+                        .ok_or_else(|| {
+                            eyre!(
+                                "unknown variable {{{name}}} at {}, expected one of: {}",
+                                location(source, offset),
+                                VARIABLES
+                                    .iter()
+                                    .map(|(known, _)| format!("{{{known}}}"))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )
+                        })?;
+
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Variable(variable));
+                }
+                '}' => {
+                    return Err(eyre!(
+                        "unmatched closing brace at {}, write }}}} for a literal brace",
+                        location(source, offset)
+                    ));
+                }
+                char => text.push(char),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self {
+            source: source.to_owned(),
+            parts,
+        })
+    }
+
+    /// This function renders the template for the given round, played by the user with the given
+    /// name.
+    pub(crate) fn render(&self, round: &Round, player: &str) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Variable(variable) => match variable {
+                    Variable::Result => round.verdict().to_owned(),
+                    Variable::Guess => round.guess().to_string(),
+                    Variable::Low => round.range().0.to_string(),
+                    Variable::High => round.range().1.to_string(),
+                    Variable::Secret if round.hint().is_some() => "hidden".to_owned(),
+                    Variable::Secret => round.secret().to_string(),
+                    Variable::Streak => round.streak().to_string(),
+                    Variable::Score => (round.score() + round.points()).to_string(),
+                    Variable::Attempts => round.attempts().to_string(),
+                    Variable::Player => player.to_owned(),
+                    Variable::Summary => round.summary(),
+                },
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = Report;
+
+    fn try_from(source: String) -> Result<Self> {
+        Self::parse(&source)
+    }
+}
+
+impl From<Template> for String {
+    fn from(template: Template) -> Self {
+        template.source
+    }
+}
+
+/// This function returns the line and column the given byte offset falls on in the given text, as
+/// shown in errors.
+fn location(source: &str, offset: usize) -> String {
+    let before = source.get(..offset).unwrap_or_default();
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+
+    format!("line {line}, column {column} of the template")
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::Result;
+
+    use super::Template;
+    use crate::utils::{RandomResult, Round};
+
+    /// This function renders the given template for a round lost guessing 42 out of `1..=100`.
+    fn render(source: &str) -> Result<String> {
+        let round = Round::new(RandomResult::Incorrect, 42, 7, (1, 100), 0, 3);
+
+        Ok(Template::parse(source)?.render(&round, "partner"))
+    }
+
+    /// This function returns the error the given template is rejected with, if any.
+    fn error(source: &str) -> Option<String> {
+        Template::parse(source).err().map(|err| err.to_string())
+    }
+
+    /// This test checks that variables are replaced with the details of the round.
+    #[test]
+    fn variables() {
+        assert_eq!(
+            render("{player} guessed {guess} in {low}..={high}, it was {secret}").ok(),
+            Some("partner guessed 42 in 1..=100, it was 7".to_owned()),
+            "every variable is replaced"
+        );
+        assert_eq!(
+            render("{result}: {score}").ok(),
+            Some("Incorrect: 3".to_owned()),
+            "a lost round adds no points to the score"
+        );
+    }
+
+    /// This test checks that braces written twice are kept as single literal braces.
+    #[test]
+    fn escaped_braces() {
+        assert_eq!(
+            render("{{guess}} is {{{guess}}}").ok(),
+            Some("{guess} is {42}".to_owned()),
+            "doubled braces are literal, even next to a variable"
+        );
+        assert_eq!(
+            render("}}{{").ok(),
+            Some("}{".to_owned()),
+            "doubled braces need not be balanced"
+        );
+    }
+
+    /// This test checks that a brace that is never closed is rejected.
+    #[test]
+    fn unclosed_brace() {
+        assert_eq!(
+            error("Guess: {guess").as_deref(),
+            Some(
+                "unclosed brace at line 1, column 8 of the template, write {{ for a literal brace"
+            ),
+            "the brace is never closed"
+        );
+    }
+
+    /// This test checks that a closing brace without an opening one is rejected.
+    #[test]
+    fn stray_closing_brace() {
+        assert_eq!(
+            error("Guess: guess}").as_deref(),
+            Some(
+                "unmatched closing brace at line 1, column 13 of the template, write }} for a \
+                 literal brace"
+            ),
+            "the brace is never opened"
+        );
+    }
+
+    /// This test checks that variables the template can't refer to are rejected, naming them.
+    #[test]
+    fn unknown_variable() {
+        assert!(
+            error("{guesses}").is_some_and(|err| err.starts_with(
+                "unknown variable {guesses} at line 1, column 1 of the template, expected one of: \
+                 {result}, {guess}"
+            )),
+            "there is no guesses variable"
+        );
+        assert!(
+            error("{}").is_some_and(|err| err.starts_with("unknown variable {} ")),
+            "a variable needs a name"
+        );
+    }
+
+    /// This test checks that errors point at the line and column of the offending brace, counting
+    /// characters rather than bytes.
+    #[test]
+    fn multi_line_location() {
+        assert!(
+            error("Range: {low}..={high}\nGuess: {guess}\n\u{a1}Ol\u{e9}! {score").is_some_and(
+                |err| err.starts_with("unclosed brace at line 3, column 7 of the template")
+            ),
+            "the brace is on the third line, after six characters"
+        );
+        assert!(
+            error("\n\n}").is_some_and(|err| err
+                .starts_with("unmatched closing brace at line 3, column 1 of the template")),
+            "the brace starts the third line"
+        );
+    }
+}
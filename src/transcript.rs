@@ -0,0 +1,90 @@
+//! This module contains support for the memory of the language model within a session. The rounds
+//! played and the replies given to them are kept as a transcript, part of which is sent along with
+//! each chat completion request so that the language model can refer back to earlier rounds.
+
+use std::collections::VecDeque;
+
+use crate::utils::{Message, Role};
+
+/// This constant holds the average amount of characters per token, used to estimate the amount of
+/// tokens a message takes up without having to tokenize it.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// This structure holds information about a single exchange with the language model.
+struct Turn {
+    /// This field refers to the message the language model was told about the round with.
+    round: String,
+    /// This field refers to the reply of the language model.
+    reply: String,
+}
+
+impl Turn {
+    /// This function returns an estimate of the amount of tokens the exchange takes up.
+    fn tokens(&self) -> usize {
+        (self.round.chars().count() + self.reply.chars().count()).div_ceil(CHARS_PER_TOKEN)
+    }
+}
+
+/// This structure holds the transcript of the exchanges with the language model in the session,
+/// along with the budget the part of it sent with each request must fit in.
+pub(crate) struct Transcript {
+    /// This field refers to the exchanges kept so far, from the oldest to the most recent.
+    turns: VecDeque<Turn>,
+    /// This field refers to the maximum amount of exchanges sent with each request. No exchanges are
+    /// kept at all if it's zero.
+    max_turns: usize,
+    /// This field refers to the estimated maximum amount of tokens the exchanges sent with each
+    /// request may take up.
+    max_tokens: usize,
+}
+
+impl Transcript {
+    /// This function builds an empty transcript with the given budget.
+    pub(crate) const fn new(max_turns: usize, max_tokens: usize) -> Self {
+        Self {
+            turns: VecDeque::new(),
+            max_turns,
+            max_tokens,
+        }
+    }
+
+    /// This function keeps the given message telling the language model about a round and the reply
+    /// to it in the transcript, forgetting the oldest exchange if there are already as many as can
+    /// be sent.
+    pub(crate) fn push(&mut self, round: String, reply: String) {
+        if self.max_turns == 0 || reply.is_empty() {
+            return;
+        }
+
+        if self.turns.len() == self.max_turns {
+            let _ = self.turns.pop_front();
+        }
+        self.turns.push_back(Turn { round, reply });
+    }
+
+    /// This function returns the messages to send ahead of the current round, which are the most
+    /// recent exchanges that fit in the token budget, in the order they took place.
+    pub(crate) fn messages(&self) -> Vec<Message> {
+        let mut tokens = 0;
+        let kept = self
+            .turns
+            .iter()
+            .rev()
+            .take_while(|turn| {
+                tokens += turn.tokens();
+                tokens <= self.max_tokens
+            })
+            .count();
+
+        self.turns
+            .iter()
+            .skip(self.turns.len() - kept)
+            .flat_map(|turn| {
+                [
+                    Message::new(Role::User, turn.round.clone()),
+                    Message::new(Role::Assistant, turn.reply.clone()),
+                ]
+            })
+            .collect()
+    }
+}
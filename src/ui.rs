@@ -8,37 +8,67 @@ use ratatui::{
     prelude::{Alignment, Buffer, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols::{bar::FULL, DOT},
-    text::Line,
-    widgets::{Block, BorderType, Borders, Paragraph, Widget, Wrap},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Padding, Paragraph, Sparkline, SparklineBar, Widget, Wrap,
+    },
 };
 
 use crate::{
+    daily::Daily,
+    fuzzy::fuzzy_match,
+    history::percentage,
     utils::{
-        EndMenuItem, GameItem, GameScreen, MainMenuItem, MenuType, OptionsMenuItem, RandomResult,
-        Screen,
+        EndMenuItem, ErrorPopupItem, GameItem, GameMode, GameScreen, MainMenuItem, MenuType,
+        ModelInfo, ModelSection, OptionsMenuItem, Overlay, RandomResult, SamplingMenuItem, Screen,
+        Usage,
     },
     App,
 };
 
-impl Widget for &mut App<'_> {
+/// This constant holds the frames of the spinner shown while the chat completion request is being
+/// processed in the background.
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+/// This constant holds the mark drawn next to the models starred in the model menu.
+const STAR: &str = "\u{2605}";
+
+impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         match &self.screen {
             Screen::MainMenu(screen) => {
-                App::main_menu(area, buf, screen);
+                self.main_menu(area, buf, screen);
             }
             Screen::OptionsMenu(screen) => {
-                App::options_menu(area, buf, screen);
+                self.options_menu(area, buf, screen);
+            }
+            Screen::SamplingMenu(screen) => {
+                self.sampling_menu(area, buf, screen);
             }
             Screen::InGame(screen) => match screen {
                 GameScreen::Game(screen) => self.take_input(area, buf, screen),
                 GameScreen::EndMenu(screen) => self.end_menu(area, buf, screen),
+                GameScreen::ErrorPopup(screen) => self.error_popup(area, buf, screen),
             },
-            Screen::ModelMenu => self.model_menu(area, buf),
+            Screen::ModelMenu(_) => self.model_menu(area, buf),
+            Screen::Statistics => self.statistics(area, buf),
+            Screen::ModeMenu(screen) => self.mode_menu(area, buf, *screen),
+            Screen::Daily => self.daily(area, buf),
         };
+        if let Some(notice) = &self.notice {
+            self.notice_bar(area, buf, notice);
+        }
+        if self.overlay == Some(Overlay::Debug) {
+            self.debug_overlay(area, buf);
+        }
     }
 }
 
-impl App<'_> {
+#[expect(
+    clippy::multiple_inherent_impl,
+    reason = "The rendering functions are kept apart from the business logic of the application."
+)]
+impl App {
     /// This function initializes the screen area and the block to be used when rendering generic
     /// menus. Generic menus are denoted by those with a similar appearance. Currently, only the
     /// main menu and the options menu are considered generic.
@@ -46,7 +76,7 @@ impl App<'_> {
         clippy::indexing_slicing,
         reason = "The collection is created in place with a small amount of elements of known index."
     )]
-    fn init_menu(area: Rect, buf: &mut Buffer, menu: MenuType) -> Rc<[Rect]> {
+    fn init_menu(&self, area: Rect, buf: &mut Buffer, menu: MenuType) -> Rc<[Rect]> {
         let screen = Layout::vertical([
             Constraint::Percentage(40),
             Constraint::Percentage(20),
@@ -55,7 +85,9 @@ impl App<'_> {
         .split(area);
         let item_count = match menu {
             MenuType::MainMenu(num) => num,
-            MenuType::OptionsMenu(num) => num,
+            MenuType::OptionsMenu(num) | MenuType::SamplingMenu(num) | MenuType::GameMode(num) => {
+                num
+            }
         };
 
         let block_space = Layout::horizontal([
@@ -71,7 +103,7 @@ impl App<'_> {
             .title_top(menu.repr())
             .title_bottom("(j) down / (k) up / (l) select")
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .border_type(BorderType::Rounded);
 
         let item_space = block.inner(block_layout);
@@ -86,44 +118,35 @@ impl App<'_> {
         clippy::indexing_slicing,
         reason = "The collection is created in place with a small amount of elements of known index."
     )]
-    #[expect(
-        clippy::missing_asserts_for_indexing,
-        reason = "The collection is created in place with a small amount of elements of known index."
-    )]
-    fn main_menu(area: Rect, buf: &mut Buffer, screen: &MainMenuItem) {
+    fn main_menu(&self, area: Rect, buf: &mut Buffer, screen: &MainMenuItem) {
         Self::clear(area, buf);
 
-        let item_layout = Self::init_menu(area, buf, MenuType::MainMenu(3));
+        let item_layout = self.init_menu(area, buf, MenuType::MainMenu(4));
 
-        let content_style = Style::default().fg(Color::White);
-        let active_content_style = content_style.bg(Color::Green);
+        let content_style = Style::default().fg(self.theme.text());
+        let active_content_style = content_style.bg(self.theme.accent());
 
-        let mut items = [
+        let items = [
             Line::raw("Play").centered(),
+            Line::raw("Statistics").centered(),
             Line::raw("Options").centered(),
             Line::raw("Exit").centered(),
         ];
-        match screen {
-            MainMenuItem::Play => {
-                items[0] = items[0].clone().style(active_content_style);
-                items[1] = items[1].clone().style(content_style);
-                items[2] = items[2].clone().style(content_style);
-            }
-            MainMenuItem::Options => {
-                items[0] = items[0].clone().style(content_style);
-                items[1] = items[1].clone().style(active_content_style);
-                items[2] = items[2].clone().style(content_style);
-            }
-            MainMenuItem::Exit => {
-                items[0] = items[0].clone().style(content_style);
-                items[1] = items[1].clone().style(content_style);
-                items[2] = items[2].clone().style(active_content_style);
-            }
-        }
+        let active = match screen {
+            MainMenuItem::Play => 0,
+            MainMenuItem::Statistics => 1,
+            MainMenuItem::Options => 2,
+            MainMenuItem::Exit => 3,
+        };
 
-        items[0].clone().render(item_layout[0], buf);
-        items[1].clone().render(item_layout[1], buf);
-        items[2].clone().render(item_layout[2], buf);
+        for (idx, item) in items.into_iter().enumerate() {
+            let style = if idx == active {
+                active_content_style
+            } else {
+                content_style
+            };
+            item.style(style).render(item_layout[idx], buf);
+        }
     }
 
     /// This function renders the options menu.
@@ -131,35 +154,126 @@ impl App<'_> {
         clippy::indexing_slicing,
         reason = "The collection is created in place with a small amount of elements of known index."
     )]
+    fn options_menu(&self, area: Rect, buf: &mut Buffer, screen: &OptionsMenuItem) {
+        Self::clear(area, buf);
+
+        let item_layout = self.init_menu(area, buf, MenuType::OptionsMenu(5));
+
+        let content_style = Style::default().fg(self.theme.text());
+        let active_content_style = content_style.bg(self.theme.accent());
+
+        let items = [
+            Line::raw("Model").centered(),
+            Line::raw(format!("Persona: {}", self.persona().name())).centered(),
+            Line::raw(format!("Difficulty: {}", self.difficulty.repr())).centered(),
+            Line::raw("Sampling").centered(),
+            Line::raw("Return").centered(),
+        ];
+        let active = match screen {
+            OptionsMenuItem::Model => 0,
+            OptionsMenuItem::Persona => 1,
+            OptionsMenuItem::Difficulty => 2,
+            OptionsMenuItem::Sampling => 3,
+            OptionsMenuItem::Return => 4,
+        };
+
+        for (idx, item) in items.into_iter().enumerate() {
+            let style = if idx == active {
+                active_content_style
+            } else {
+                content_style
+            };
+            item.style(style).render(item_layout[idx], buf);
+        }
+    }
+
+    /// This function renders the sampling menu, with the current value of each sampling parameter.
     #[expect(
-        clippy::missing_asserts_for_indexing,
+        clippy::indexing_slicing,
         reason = "The collection is created in place with a small amount of elements of known index."
     )]
-    fn options_menu(area: Rect, buf: &mut Buffer, screen: &OptionsMenuItem) {
+    fn sampling_menu(&self, area: Rect, buf: &mut Buffer, screen: &SamplingMenuItem) {
         Self::clear(area, buf);
 
-        let item_layout = Self::init_menu(area, buf, MenuType::OptionsMenu(2));
+        let item_layout = self.init_menu(area, buf, MenuType::SamplingMenu(5));
 
-        let content_style = Style::default().fg(Color::White);
-        let active_content_style = content_style.bg(Color::Green);
+        let content_style = Style::default().fg(self.theme.text());
+        let active_content_style = content_style.bg(self.theme.accent());
 
-        let mut items = [
-            Line::raw("Model").centered(),
+        let or_default = |value: Option<String>| value.unwrap_or_else(|| "default".to_owned());
+        let items = [
+            Line::raw(format!(
+                "Temperature: {}",
+                or_default(
+                    self.sampling
+                        .temperature()
+                        .map(|value| format!("{value:.1}"))
+                )
+            ))
+            .centered(),
+            Line::raw(format!(
+                "Top-p: {}",
+                or_default(self.sampling.top_p().map(|value| format!("{value:.2}")))
+            ))
+            .centered(),
+            Line::raw(format!(
+                "Max tokens: {}",
+                or_default(self.sampling.max_tokens().map(|value| value.to_string()))
+            ))
+            .centered(),
+            Line::raw(format!(
+                "Seed: {}",
+                self.sampling
+                    .seed()
+                    .map_or_else(|| "off".to_owned(), |value| value.to_string())
+            ))
+            .centered(),
             Line::raw("Return").centered(),
         ];
-        match screen {
-            OptionsMenuItem::Model => {
-                items[0] = items[0].clone().style(active_content_style);
-                items[1] = items[1].clone().style(content_style);
-            }
-            OptionsMenuItem::Return => {
-                items[0] = items[0].clone().style(content_style);
-                items[1] = items[1].clone().style(active_content_style);
-            }
+        let active = match screen {
+            SamplingMenuItem::Temperature => 0,
+            SamplingMenuItem::TopP => 1,
+            SamplingMenuItem::MaxTokens => 2,
+            SamplingMenuItem::Seed => 3,
+            SamplingMenuItem::Return => 4,
+        };
+
+        for (idx, item) in items.into_iter().enumerate() {
+            let style = if idx == active {
+                active_content_style
+            } else {
+                content_style
+            };
+            item.style(style).render(item_layout[idx], buf);
         }
+    }
+
+    /// This function renders the game mode menu.
+    #[expect(
+        clippy::indexing_slicing,
+        reason = "The collection is created in place with a small amount of elements of known index."
+    )]
+    fn mode_menu(&self, area: Rect, buf: &mut Buffer, screen: GameMode) {
+        Self::clear(area, buf);
+
+        let item_layout = self.init_menu(area, buf, MenuType::GameMode(3));
+
+        let content_style = Style::default().fg(self.theme.text());
+        let active_content_style = content_style.bg(self.theme.accent());
 
-        items[0].clone().render(item_layout[0], buf);
-        items[1].clone().render(item_layout[1], buf);
+        for (idx, mode) in [GameMode::OneShot, GameMode::Classic, GameMode::Daily]
+            .into_iter()
+            .enumerate()
+        {
+            let style = if mode == screen {
+                active_content_style
+            } else {
+                content_style
+            };
+            Line::styled(mode.repr(), style)
+                .centered()
+                .render(item_layout[idx], buf);
+        }
     }
 
     /// This function renders the model menu.
@@ -186,14 +300,40 @@ impl App<'_> {
             Constraint::Percentage(30),
         ])
         .split(space)[1];
+        let [space, details_space] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(9)]).areas(space);
+
+        self.model_details(details_space, buf);
 
         let model_list_block = Block::bordered()
-            .title_top("Model list")
-            .title_bottom(Line::raw("(j) down / (k) up / (l) select / (h) return"))
+            .title_top(match (&self.model_refresh, &self.model_refresh_error) {
+                (Some(_), _) => "Model list (refreshing)",
+                (None, Some(_)) => "Model list (refresh failed)",
+                (None, None) => "Model list",
+            })
+            .title_bottom(Line::raw(if self.model_query.is_some() {
+                "(ret) select / (esc) stop searching"
+            } else {
+                "(j) down / (k) up / (/) search / (s) star / (r) refresh / (l) select / (h) return"
+            }))
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .border_type(BorderType::Rounded);
-        let list_space = model_list_block.inner(space);
+        let mut list_space = model_list_block.inner(space);
+
+        model_list_block.render(space, buf);
+
+        let content_style = Style::default().fg(self.theme.text());
+        let active_content_style = content_style.bg(self.theme.accent());
+
+        let query = self.model_query.clone();
+        if let Some(query) = &query {
+            let [search_space, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(list_space);
+            Line::styled(format!("/{query}{FULL}"), content_style).render(search_space, buf);
+            list_space = rest;
+        }
+
         let list_space =
             Layout::horizontal([Constraint::Percentage(5), Constraint::Percentage(95)])
                 .split(list_space);
@@ -207,51 +347,186 @@ impl App<'_> {
         let selector_space = list_space[0];
         let model_space = list_space[1];
 
-        let selector_space_layout =
-            Layout::vertical(vec![Constraint::Max(1); selector_space.height as usize])
-                .split(selector_space);
-        let model_space_layout =
-            Layout::vertical(vec![Constraint::Max(1); model_space.height as usize])
-                .split(model_space);
+        // The viewport may have been resized since it was last drawn.
+        self.model_view_height = model_space.height.into();
+        self.scroll_to_selected();
 
-        model_list_block.render(space, buf);
+        let rows = Layout::vertical(vec![Constraint::Max(1); self.model_view_height])
+            .split(model_space)
+            .iter()
+            .zip(
+                Layout::vertical(vec![Constraint::Max(1); self.model_view_height])
+                    .split(selector_space)
+                    .iter(),
+            )
+            .map(|(&model_space, &selector_space)| (model_space, selector_space))
+            .collect::<Vec<_>>();
 
-        let content_style = Style::default().fg(Color::White);
-        let active_content_style = content_style.bg(Color::Green);
+        let entries = self.models.iter().enumerate().flat_map(|(idx, model)| {
+            self.model_section_start(idx)
+                .map(|section| (idx, None, Some(section)))
+                .into_iter()
+                .chain([(idx, Some(model), None)])
+        });
 
-        self.models_view.clear();
-        self.selectors_view.clear();
-        for model in self.models.iter().skip(self.model_view_offset as usize) {
-            if *model == self.model_view_selected {
-                if *model == self.model {
-                    self.selectors_view
-                        .push(Line::styled(DOT, active_content_style).alignment(Alignment::Center));
-                } else {
-                    self.selectors_view
-                        .push(Line::styled(" ", active_content_style));
-                }
-                self.models_view
-                    .push(Line::styled(model.to_owned(), active_content_style));
+        for ((idx, model, section), (model_space, selector_space)) in
+            entries.skip(self.model_view_offset).zip(rows)
+        {
+            let Some(model) = model else {
+                Line::styled(
+                    section.map(ModelSection::title).unwrap_or_default(),
+                    Style::default()
+                        .fg(self.theme.accent())
+                        .add_modifier(Modifier::BOLD),
+                )
+                .render(model_space, buf);
+                continue;
+            };
+
+            let style = if idx == self.model_view_selected {
+                active_content_style
+            } else {
+                content_style
+            };
+            self.model_entry(model, query.as_deref(), style)
+                .render(model_space, buf);
+
+            if model.id() == self.model {
+                Line::styled(DOT, style)
+                    .alignment(Alignment::Center)
+                    .render(selector_space, buf);
             } else {
-                if *model == self.model {
-                    self.selectors_view
-                        .push(Line::styled(DOT, content_style).alignment(Alignment::Center));
+                Line::styled(" ", style).render(selector_space, buf);
+            }
+        }
+    }
+
+    /// This function returns the line the given model is listed with in the model menu, in the given
+    /// style. The characters matching the query the menu is searched with are highlighted, and
+    /// starred models are marked as such.
+    fn model_entry(&self, model: &ModelInfo, query: Option<&str>, style: Style) -> Line<'static> {
+        let matched = query
+            .and_then(|query| fuzzy_match(query, model.id()))
+            .map(|found| found.positions().to_vec())
+            .unwrap_or_default();
+        let favorite = self
+            .favorite_models
+            .iter()
+            .any(|favorite| favorite == model.id());
+
+        Line::from(
+            model
+                .id()
+                .chars()
+                .enumerate()
+                .map(|(position, char)| {
+                    if matched.contains(&position) {
+                        Span::styled(
+                            char.to_string(),
+                            style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                        )
+                    } else {
+                        Span::styled(char.to_string(), style)
+                    }
+                })
+                .chain(favorite.then(|| Span::styled(format!(" {STAR}"), style)))
+                .collect::<Vec<_>>(),
+        )
+        .style(style)
+    }
+
+    /// This function renders the details of the model selected in the model menu, along with the
+    /// filters applied to the list of models.
+    fn model_details(&self, area: Rect, buf: &mut Buffer) {
+        let max_price = self
+            .model_filter
+            .max_price()
+            .map_or_else(|| "any".to_owned(), Self::price);
+        let details_block = Block::bordered()
+            .title_top("Details")
+            .title_bottom(format!(
+                "(f) free only: {} / (p) max: {max_price}",
+                if self.model_filter.free_only() {
+                    "on"
                 } else {
-                    self.selectors_view.push(Line::styled(" ", content_style));
+                    "off"
                 }
-                self.models_view
-                    .push(Line::styled(model.to_owned(), content_style));
+            ))
+            .title_alignment(Alignment::Center)
+            .style(self.theme.accent())
+            .border_type(BorderType::Rounded);
+
+        let text = match self.selected_model() {
+            Some(model) => {
+                let price = match model.prices() {
+                    _ if model.is_free() => "free".to_owned(),
+                    Some((prompt, completion)) => format!(
+                        "{} prompt / {} completion",
+                        Self::price(prompt),
+                        Self::price(completion)
+                    ),
+                    None => "unknown".to_owned(),
+                };
+                let context = model
+                    .context_length()
+                    .map_or_else(|| "unknown".to_owned(), |tokens| format!("{tokens} tokens"));
+
+                vec![
+                    Line::styled(
+                        model.name().to_owned(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Line::raw(format!("Context: {context}")),
+                    Line::raw(format!("Price: {price}")),
+                    Line::raw(model.description().to_owned()),
+                ]
             }
+            None => vec![Line::raw("No model matches the filters or the search")],
+        };
+
+        Paragraph::new(text)
+            .style(Style::default().fg(self.theme.text()))
+            .block(details_block)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+
+    /// This function formats the given price in US dollars per million tokens.
+    fn price(price: f64) -> String {
+        format!("${price:.2}/M")
+    }
+
+    /// This function formats the given usage as the amount of tokens and, if known, the cost in US
+    /// dollars, which is marked as a lower bound if the cost of some of the requests is unknown.
+    fn usage(usage: Usage) -> String {
+        match usage.cost() {
+            Some(cost) if usage.is_lower_bound() => {
+                format!("{} tokens, at least ${cost:.4}", usage.tokens())
+            }
+            Some(cost) => format!("{} tokens, ${cost:.4}", usage.tokens()),
+            None => format!("{} tokens", usage.tokens()),
         }
-        self.models_view.truncate(model_space.height as usize);
-        self.selectors_view.truncate(selector_space.height as usize);
+    }
 
-        for (idx, model) in self.models_view.iter().enumerate() {
-            model.render(model_space_layout[idx], buf);
+    /// This function renders the line below the score bar with the given usage of the round if any
+    /// and the usage of the session once there's any. Once the spend cap is exceeded, the line says
+    /// so.
+    fn usage_line(&self, area: Rect, buf: &mut Buffer, round_usage: Option<Usage>) {
+        let mut parts = Vec::new();
+        if let Some(round_usage) = round_usage {
+            parts.push(format!("Round: {}", Self::usage(round_usage)));
+        }
+        if self.session_usage.tokens() > 0 {
+            parts.push(format!("Session: {}", Self::usage(self.session_usage)));
         }
-        for (idx, selector) in self.selectors_view.iter().enumerate() {
-            selector.render(selector_space_layout[idx], buf);
+        if self.spend_cap_exceeded() {
+            parts.push("Spend cap reached".to_owned());
         }
+
+        Line::raw(parts.join(" / "))
+            .style(Style::default().fg(self.theme.text()))
+            .centered()
+            .render(area, buf);
     }
 
     /// This function renders the prompts to take ranged input and regular guess input from the
@@ -279,66 +554,60 @@ impl App<'_> {
             Constraint::Percentage(40),
         ])
         .split(space[1])[1];
+        let footer = Layout::vertical([Constraint::Max(1), Constraint::Max(1)])
+            .flex(Flex::End)
+            .split(space[2]);
         let score_space = Layout::horizontal([
             Constraint::Percentage(40),
             Constraint::Percentage(100),
             Constraint::Percentage(40),
         ])
         .flex(Flex::End)
-        .split(space[2]);
-        let score_space = Layout::vertical([Constraint::Max(1)])
-            .flex(Flex::End)
-            .split(score_space[1])[0];
-
-        let layout = if self.extra_line_help || self.processing_request {
-            Layout::vertical([Constraint::Max(3), Constraint::Max(3), Constraint::Max(1)])
-                .flex(Flex::Center)
-                .split(main_space)
-        } else {
-            Layout::vertical([Constraint::Max(3), Constraint::Max(3)])
-                .flex(Flex::Center)
-                .split(main_space)
-        };
+        .split(footer[0])[1];
+
+        let mut constraints = vec![Constraint::Max(3), Constraint::Max(3)];
+        if self.mode.is_classic() {
+            constraints.push(Constraint::Max(3));
+        }
+        if let Some(err) = &self.input_error {
+            let len = Self::compute_wrapping_width(err, main_space.width);
+            constraints.push(Constraint::Max(u16::try_from(len).unwrap_or(u16::MAX) + 1));
+        } else if self.processing_request() {
+            constraints.push(Constraint::Max(1));
+        }
+        let layout = Layout::vertical(constraints)
+            .flex(Flex::Center)
+            .split(main_space);
+        let line_space = layout[layout.len() - 1];
 
         let score_block = Block::new()
             .title_top(format!("Score: {}", self.score))
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .borders(Borders::TOP);
 
         score_block.render(score_space, buf);
+        self.usage_line(footer[1], buf, None);
 
         let ranged_input_block = Block::bordered()
-            .title_top("Input a range in the format n..m where n < m")
+            .title_top(if self.classic.is_some() {
+                "Range locked for this round"
+            } else {
+                "Input a range such as -50..=50"
+            })
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .border_type(BorderType::Rounded);
         let guess_input_block = Block::bordered()
             .title_top("Input a number in the above range")
             .title_bottom("(tab) switch between panels / (ret) continue")
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .border_type(BorderType::Rounded);
-        if self.extra_line_help {
-            let help_line = Block::new()
-                .title_top("Incorrect input")
-                .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
-                .title_alignment(Alignment::Center)
-                .borders(Borders::TOP);
+        self.notice_line(line_space, buf);
 
-            help_line.render(layout[2], buf);
-        } else if self.processing_request {
-            let processing_text = Block::new()
-                .title_top(format!(" {DOT} Processing {DOT} "))
-                .title_alignment(Alignment::Center)
-                .style(
-                    Style::default()
-                        .fg(Color::White)
-                        .add_modifier(Modifier::BOLD),
-                )
-                .borders(Borders::TOP);
-
-            processing_text.render(layout[2], buf);
+        if self.mode.is_classic() {
+            self.classic_status(layout[2], buf);
         }
 
         let ranged_input_space = ranged_input_block.inner(layout[0]);
@@ -348,8 +617,9 @@ impl App<'_> {
         guess_input_block.render(layout[1], buf);
 
         let mut ranged_input =
-            Line::styled(self.range_input.clone(), Color::White).alignment(Alignment::Center);
-        let mut input = Line::styled(self.input.clone(), Color::White).alignment(Alignment::Center);
+            Line::styled(self.range_input.clone(), self.theme.text()).alignment(Alignment::Center);
+        let mut input =
+            Line::styled(self.input.clone(), self.theme.text()).alignment(Alignment::Center);
         match screen {
             GameItem::Range => {
                 ranged_input.push_span(FULL);
@@ -363,6 +633,107 @@ impl App<'_> {
         input.render(guess_input_space, buf);
     }
 
+    /// This function renders the line below the input prompts, which either notifies the user their
+    /// input is invalid, along with the reason why, or that the chat completion request is being
+    /// processed, if any of those is the case.
+    #[expect(
+        clippy::indexing_slicing,
+        reason = "The index is always kept within the bounds of the collection by the modulo."
+    )]
+    fn notice_line(&self, line_space: Rect, buf: &mut Buffer) {
+        if let Some(err) = &self.input_error {
+            let help_line = Block::new()
+                .title_top("Incorrect input")
+                .style(
+                    Style::default()
+                        .fg(self.theme.error())
+                        .add_modifier(Modifier::BOLD),
+                )
+                .title_alignment(Alignment::Center)
+                .borders(Borders::TOP);
+            let reason = Paragraph::new(err.as_str())
+                .style(Style::default().fg(self.theme.error()))
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+
+            reason.render(help_line.inner(line_space), buf);
+            help_line.render(line_space, buf);
+        } else if self.processing_request() {
+            let frame = SPINNER[self.tick % SPINNER.len()];
+            let attempt = match (self.attempt, self.reply_model != self.model) {
+                (_, true) => format!(" with {}", self.reply_model),
+                (2.., false) => format!(" (attempt {})", self.attempt),
+                _ => String::new(),
+            };
+            let processing_text = Block::new()
+                .title_top(format!(
+                    " {frame} Processing{attempt} (esc) cancel {frame} "
+                ))
+                .title_alignment(Alignment::Center)
+                .style(
+                    Style::default()
+                        .fg(self.theme.text())
+                        .add_modifier(Modifier::BOLD),
+                )
+                .borders(Borders::TOP);
+
+            processing_text.render(line_space, buf);
+        }
+    }
+
+    /// This function renders the status of the round of the classic game mode being played below the
+    /// input prompts; namely, the guess the user is at and the hint for their last guess, if any.
+    fn classic_status(&self, area: Rect, buf: &mut Buffer) {
+        // Before the round starts, the amount of guesses is only known once the range is valid.
+        let (guess, max_attempts, hint) = self.classic.as_ref().map_or_else(
+            || {
+                let max_attempts = self
+                    .parse_range()
+                    .ok()
+                    .map(|range| self.difficulty.attempts(range));
+                (1, max_attempts, None)
+            },
+            |classic| {
+                (
+                    classic.attempts() + 1,
+                    Some(classic.max_attempts()),
+                    classic.hint(),
+                )
+            },
+        );
+        let (text, color) = match hint {
+            Some(_) if !self.chat_completion_output.is_empty() => {
+                (self.chat_completion_output.clone(), self.persona_color())
+            }
+            Some(hint) => (format!("{}!", hint.repr()), self.theme.text()),
+            None => (String::new(), self.theme.text()),
+        };
+
+        let status_block = Block::new()
+            .title_top(max_attempts.map_or_else(
+                || format!("Guess {guess}"),
+                |max_attempts| format!("Guess {guess} of {max_attempts}"),
+            ))
+            .title_alignment(Alignment::Center)
+            .style(self.theme.accent())
+            .borders(Borders::TOP);
+
+        Paragraph::new(text)
+            .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .block(status_block)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+
+    /// This function returns the color the replies of the persona the language model speaks as are
+    /// drawn with, which is the accent color unless the persona has one of its own.
+    fn persona_color(&self) -> Color {
+        self.persona()
+            .color()
+            .unwrap_or_else(|| self.theme.accent())
+    }
+
     /// This function renders the end game menu, as well as the prompt to continue.
     #[expect(
         clippy::indexing_slicing,
@@ -387,15 +758,15 @@ impl App<'_> {
             Constraint::Percentage(40),
         ])
         .split(space[1])[1];
+        let footer = Layout::vertical([Constraint::Max(1), Constraint::Max(1)])
+            .flex(Flex::End)
+            .split(space[2]);
         let score_space = Layout::horizontal([
             Constraint::Percentage(40),
             Constraint::Percentage(100),
             Constraint::Percentage(40),
         ])
-        .split(space[2]);
-        let score_space = Layout::vertical([Constraint::Max(1)])
-            .flex(Flex::End)
-            .split(score_space[1])[0];
+        .split(footer[0])[1];
 
         let layout = Layout::vertical([Constraint::Min(3), Constraint::Max(4)])
             .flex(Flex::Center)
@@ -418,10 +789,11 @@ impl App<'_> {
         let score_block = Block::new()
             .title_top(format!("Score: {}", self.score))
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .borders(Borders::TOP);
 
         score_block.render(score_space, buf);
+        self.usage_line(footer[1], buf, self.round_usage);
 
         let result_block = Block::bordered()
             .title_top({
@@ -430,28 +802,34 @@ impl App<'_> {
                     RandomResult::Incorrect => "Incorrect",
                 }
             })
+            .title_bottom(format!("{} ({})", self.persona().name(), self.reply_model))
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.persona_color())
             .border_type(BorderType::Rounded);
         let prompt_block = Block::new()
             .title_top("Continue for another game?")
             .title_bottom("(j) down / (k) up / (l) select")
             .title_alignment(Alignment::Center)
-            .style(Color::Green)
+            .style(self.theme.accent())
             .borders(Borders::TOP | Borders::BOTTOM);
 
         let prompt_space = prompt_block.inner(layout[1]);
 
         prompt_block.render(layout[1], buf);
 
-        let result_text = Paragraph::new(self.chat_completion_output.clone())
-            .style(Color::Green)
+        let mut output = self.chat_completion_output.clone();
+        if self.processing_request() {
+            output.push_str(FULL);
+        }
+        let result_text = Paragraph::new(output)
             .block(result_block)
             .wrap(Wrap { trim: true });
         result_text.render(layout[0], buf);
 
-        let content_style = Style::default().fg(Color::Green);
-        let active_content_style = Style::default().fg(Color::White).bg(Color::Green);
+        let content_style = Style::default().fg(self.theme.accent());
+        let active_content_style = Style::default()
+            .fg(self.theme.text())
+            .bg(self.theme.accent());
 
         let prompt_layout =
             Layout::vertical([Constraint::Max(1), Constraint::Max(1)]).split(prompt_space);
@@ -473,6 +851,299 @@ impl App<'_> {
         no.render(prompt_layout[1], buf);
     }
 
+    /// This function renders the debug overlay in the top right corner of the screen, with the seed
+    /// of the RNG the numbers are drawn with among other details of the session.
+    fn debug_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let lines = [
+            format!("Seed: {}", self.seed),
+            format!("Model: {}", self.model),
+            format!("Persona: {}", self.persona().id()),
+        ];
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count() + 2)
+            .max()
+            .unwrap_or_default();
+
+        let [space] =
+            Layout::horizontal([Constraint::Length(width.try_into().unwrap_or(u16::MAX))])
+                .flex(Flex::End)
+                .areas(area);
+        let [space] = Layout::vertical([Constraint::Length(
+            (lines.len() + 2).try_into().unwrap_or(u16::MAX),
+        )])
+        .areas(space);
+
+        Self::clear(space, buf);
+
+        let debug_block = Block::bordered()
+            .title_top("Debug")
+            .title_alignment(Alignment::Center)
+            .style(self.theme.accent())
+            .border_type(BorderType::Rounded);
+        Paragraph::new(lines.map(Line::raw).to_vec())
+            .style(Style::default().fg(self.theme.text()))
+            .block(debug_block)
+            .render(space, buf);
+    }
+
+    /// This function renders the given notice on the bottom line of the screen, on top of whatever
+    /// was drawn there.
+    fn notice_bar(&self, area: Rect, buf: &mut Buffer, notice: &str) {
+        let [space] = Layout::vertical([Constraint::Length(1)])
+            .flex(Flex::End)
+            .areas(area);
+
+        Self::clear(space, buf);
+
+        Line::raw(notice)
+            .style(
+                Style::default()
+                    .fg(self.theme.error())
+                    .add_modifier(Modifier::BOLD),
+            )
+            .centered()
+            .render(space, buf);
+    }
+
+    /// This function renders the error popup, with the reason why the chat completion request failed
+    /// and the prompt on how to carry on.
+    #[expect(
+        clippy::indexing_slicing,
+        reason = "The collection is created in place with a small amount of elements of known index."
+    )]
+    fn error_popup(&self, area: Rect, buf: &mut Buffer, screen: &ErrorPopupItem) {
+        Self::clear(area, buf);
+
+        let main_space = Layout::horizontal([
+            Constraint::Percentage(40),
+            Constraint::Percentage(100),
+            Constraint::Percentage(40),
+        ])
+        .split(area)[1];
+
+        let message = self
+            .error
+            .as_ref()
+            .map_or_else(|| "unknown error".to_owned(), ToString::to_string);
+        let len = Self::compute_wrapping_width(&message, main_space.width - 2);
+
+        let layout = Layout::vertical([
+            Constraint::Length(
+                (len + 2)
+                    .clamp(3, main_space.height as usize / 2)
+                    .try_into()
+                    .expect("conversion failed while creating main layout"),
+            ),
+            Constraint::Max(5),
+        ])
+        .flex(Flex::Center)
+        .split(main_space);
+
+        let error_block = Block::bordered()
+            .title_top("Request failed")
+            .title_alignment(Alignment::Center)
+            .style(self.theme.error())
+            .border_type(BorderType::Rounded);
+        let prompt_block = Block::new()
+            .title_top("How do you want to carry on?")
+            .title_bottom("(j) down / (k) up / (l) select")
+            .title_alignment(Alignment::Center)
+            .style(self.theme.accent())
+            .borders(Borders::TOP | Borders::BOTTOM);
+
+        let prompt_space = prompt_block.inner(layout[1]);
+
+        prompt_block.render(layout[1], buf);
+
+        let error_text = Paragraph::new(message)
+            .style(self.theme.text())
+            .block(error_block)
+            .wrap(Wrap { trim: true });
+        error_text.render(layout[0], buf);
+
+        let content_style = Style::default().fg(self.theme.accent());
+        let active_content_style = Style::default()
+            .fg(self.theme.text())
+            .bg(self.theme.accent());
+
+        let prompt_layout = Layout::vertical([Constraint::Max(1); 3]).split(prompt_space);
+
+        let mut items = [
+            Line::raw("Retry").centered(),
+            Line::raw("Switch model").centered(),
+            Line::raw("Offline reply").centered(),
+        ];
+        let active = match screen {
+            ErrorPopupItem::Retry => 0,
+            ErrorPopupItem::SwitchModel => 1,
+            ErrorPopupItem::Offline => 2,
+        };
+
+        for (idx, item) in items.iter_mut().enumerate() {
+            let style = if idx == active {
+                active_content_style
+            } else {
+                content_style
+            };
+            item.clone().style(style).render(prompt_layout[idx], buf);
+        }
+    }
+
+    /// This function renders the statistics screen, with a summary of the rounds played so far and a
+    /// sparkline of the most recent ones, where won rounds show as tall bars and lost ones as short
+    /// bars.
+    #[expect(
+        clippy::indexing_slicing,
+        reason = "The collection is created in place with a small amount of elements of known index."
+    )]
+    #[expect(
+        clippy::missing_asserts_for_indexing,
+        reason = "The collection is created in place with a small amount of elements of known index."
+    )]
+    fn statistics(&self, area: Rect, buf: &mut Buffer) {
+        Self::clear(area, buf);
+
+        let main_space = Layout::horizontal([
+            Constraint::Percentage(40),
+            Constraint::Percentage(100),
+            Constraint::Percentage(40),
+        ])
+        .split(area)[1];
+
+        let block = Block::bordered()
+            .title_top("Statistics")
+            .title_bottom("(h) return")
+            .title_alignment(Alignment::Center)
+            .style(self.theme.accent())
+            .border_type(BorderType::Rounded);
+        // The sparkline spans the whole width of the block, one bar per round.
+        let stats = self
+            .history
+            .statistics(main_space.width.saturating_sub(2).into());
+
+        let text_style = Style::default().fg(self.theme.text());
+        let mut lines = vec![
+            Line::styled(format!("Rounds played: {}", stats.played()), text_style),
+            Line::styled(
+                format!(
+                    "Rounds won: {} ({}%)",
+                    stats.won(),
+                    percentage(stats.won(), stats.played())
+                ),
+                text_style,
+            ),
+            Line::styled(
+                format!("Longest streak: {}", stats.longest_streak()),
+                text_style,
+            ),
+            Line::raw(""),
+            Line::styled(
+                "Win rate by range size",
+                text_style.add_modifier(Modifier::BOLD),
+            ),
+        ];
+        for (label, played, won) in stats.by_range() {
+            let rate = if played == 0 {
+                "-".to_owned()
+            } else {
+                format!("{}% of {played}", percentage(won, played))
+            };
+            lines.push(Line::styled(format!("{label}: {rate}"), text_style));
+        }
+
+        let height =
+            u16::try_from(lines.len()).expect("conversion failed while creating main layout") + 6;
+        let space = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .split(main_space)[0];
+        let inner = block.inner(space);
+        let layout = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).split(inner);
+
+        block.render(space, buf);
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .render(layout[0], buf);
+
+        let bars: Vec<SparklineBar> = stats
+            .recent()
+            .iter()
+            .map(|&won| {
+                if won {
+                    SparklineBar::from(4).style(Style::default().fg(self.theme.accent()))
+                } else {
+                    SparklineBar::from(1).style(Style::default().fg(self.theme.error()))
+                }
+            })
+            .collect();
+        Sparkline::default()
+            .block(
+                Block::new()
+                    .title_top("Recent rounds")
+                    .borders(Borders::TOP),
+            )
+            .data(bars)
+            .max(4)
+            .render(layout[1], buf);
+    }
+
+    /// This function renders the daily challenge screen, with the summary of the challenge of the day
+    /// to be shared.
+    #[expect(
+        clippy::indexing_slicing,
+        reason = "The collection is created in place with a small amount of elements of known index."
+    )]
+    fn daily(&self, area: Rect, buf: &mut Buffer) {
+        Self::clear(area, buf);
+
+        let main_space = Layout::horizontal([
+            Constraint::Percentage(40),
+            Constraint::Percentage(100),
+            Constraint::Percentage(40),
+        ])
+        .split(area)[1];
+
+        let block = Block::bordered()
+            .title_top("Daily challenge")
+            .title_bottom("(h) return")
+            .title_alignment(Alignment::Center)
+            .style(self.theme.accent())
+            .border_type(BorderType::Rounded);
+
+        let text_style = Style::default().fg(self.theme.text());
+        let summary = self.daily.as_ref().map(Daily::summary).unwrap_or_default();
+        let notice = "Come back tomorrow for the next challenge.";
+        // The padding and the borders take up two columns.
+        let len = summary
+            .lines()
+            .chain([notice])
+            .map(|line| Self::compute_wrapping_width(line, main_space.width.saturating_sub(4)))
+            .sum::<usize>()
+            + 1;
+
+        let mut lines: Vec<Line> = summary
+            .lines()
+            .map(|line| Line::styled(line.to_owned(), text_style))
+            .collect();
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            notice,
+            text_style.add_modifier(Modifier::BOLD),
+        ));
+
+        let height = u16::try_from(len).expect("conversion failed while creating main layout") + 4;
+        let space = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .split(main_space)[0];
+
+        Paragraph::new(lines)
+            .block(block.padding(Padding::uniform(1)))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(space, buf);
+    }
+
     /// This function computes the amount of times a given string would be partitioned to wrap it
     /// into a given width.
     fn compute_wrapping_width(input_string: &str, max_width: u16) -> usize {
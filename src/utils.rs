@@ -1,25 +1,78 @@
 //! This module contains every structure and enumeration in the program, as well as their
 //! corresponding implementations, if any, that are not part of the core functioning of the former.
-//! These include all but the [`crate::App`] structure. There is also a function used to validate
-//! the model input from the user in the command line that is included in this file and only used
-//! here as well. This function uses another auxiliary function to actually fetch the models that is
-//! used in other parts of the program.
+//! These include all but the [`crate::App`] structure.
 
-use std::sync::LazyLock;
+use std::{
+    cmp::Ordering,
+    num::{IntErrorKind, ParseIntError},
+    path::PathBuf,
+};
 
-use clap::Parser;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use color_eyre::{eyre::eyre, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
-/// This static contains the message to issue to the language model as part of the system prompt in
-/// the chat completion request to the OpenRouter API.
-pub(crate) static LLM_INPUT: LazyLock<&str> = LazyLock::new(|| {
-    "You will answer only to \"Correct\" or \"Incorrect.\" These correspond to either a\
-notification that a user got a number right in a number guessing game or not, respectively. Your\
-task is to, depending on whether you were notified they got it right, or not, to return a\
-cowboy-like answer to the user. Make it a short text. Include just your answer and nothing more.\
-Don't include emoji or otherwise non-verbal content."
-});
+use crate::{
+    config::{Config, Difficulty, HintStyle, Sampling},
+    error::{ApiError, ErrorBody},
+};
+
+/// This constant holds the pattern of the ranges input by the user, which follow the syntax of the
+/// ranges of Rust.
+pub(crate) const RANGE_PATTERN: &str = r"\A(?<start>-?\d+)\.\.(?<inclusive>=)?(?<end>-?\d+)\z";
+
+/// This type holds the numbers the game is played with, which are wide enough for ranges of any
+/// signed or unsigned 64-bit numbers.
+pub(crate) type Number = i128;
+
+/// This function parses the given text into a number of the game.
+///
+/// # Errors
+///
+/// - [`color_eyre::Report`] if the text is not a whole number, or if it doesn't fit in a
+///   [`Number`], telling which bound it goes past.
+pub(crate) fn parse_number(text: &str) -> Result<Number> {
+    text.parse().map_err(|err: ParseIntError| match err.kind() {
+        IntErrorKind::PosOverflow => {
+            eyre!("{text} is too large, the largest number is {}", Number::MAX)
+        }
+        IntErrorKind::NegOverflow => eyre!(
+            "{text} is too small, the smallest number is {}",
+            Number::MIN
+        ),
+        IntErrorKind::Empty => eyre!("expected a number"),
+        _ => eyre!("{text} is not a whole number"),
+    })
+}
+
+/// This function parses the given range, matched with the given pattern, into its lower and upper
+/// bounds, both of which are inclusive. As in Rust, the upper bound of the range is only included if
+/// it's preceded by `=`.
+///
+/// # Errors
+///
+/// - [`color_eyre::Report`] if the text is not a range, if any of its bounds doesn't fit in a
+///   [`Number`], or if the range holds less than two numbers.
+pub(crate) fn parse_range(pattern: &Regex, text: &str) -> Result<(Number, Number)> {
+    let captures = pattern
+        .captures(text)
+        .ok_or_else(|| eyre!("expected a range such as 1..=100 or -50..50, got {text:?}"))?;
+    let start = parse_number(&captures["start"])?;
+    let end = parse_number(&captures["end"])?;
+    // An exclusive upper bound is turned into an inclusive one, which can only fail if it's the
+    // smallest number, and so if the range is empty.
+    let end = if captures.name("inclusive").is_some() {
+        Some(end)
+    } else {
+        end.checked_sub(1)
+    };
+
+    match end {
+        Some(end) if start < end => Ok((start, end)),
+        _ => Err(eyre!("the range {text} holds less than two numbers")),
+    }
+}
 
 /// This enumeration holds information about the deterministic screen states in which the user may
 /// find himself while playing the game. It is mostly used for deciding what type of interface
@@ -35,10 +88,172 @@ pub(crate) enum Screen {
     /// This variant refers to the state of being in-game. It thus comes accompanied of other
     /// screenful states.
     InGame(GameScreen),
+    /// This variant refers to the sampling menu, entered from the options menu. Its states
+    /// correspond with the selection of items in the menu.
+    SamplingMenu(SamplingMenuItem),
     /// This variant refers to the state of being in the model menu. Even though it's not part of
     /// the menus found primarily at the start screen, it does require different rendering and thus
-    /// holds its own individual screen state.
-    ModelMenu,
+    /// holds its own individual screen state. Its state corresponds with the screen it was entered
+    /// from, to which it returns.
+    ModelMenu(MenuOrigin),
+    /// This variant refers to the state of being in the statistics screen, with a summary of the
+    /// rounds played so far.
+    Statistics,
+    /// This variant refers to the state of being in the game mode menu, right before starting the
+    /// game. Its states correspond with the selection of items in the menu.
+    ModeMenu(GameMode),
+    /// This variant refers to the state of being in the daily challenge screen, with the summary of
+    /// the challenge of the day once it's over.
+    Daily,
+}
+
+/// This enumeration holds information about the game modes that can be played. It is also used for
+/// the selection of items in the game mode menu.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum GameMode {
+    /// This variant refers to the original mode, where a number is drawn for every guess, so that
+    /// guessing it right is a matter of luck.
+    #[default]
+    OneShot,
+    /// This variant refers to the mode where the number is drawn once per round, and the user is
+    /// given a few attempts at it, being told after each wrong guess whether the number is higher or
+    /// lower.
+    Classic,
+    /// This variant refers to the mode played as the classic one, but where the range and the number
+    /// are drawn from the date, so that everyone gets the same challenge on the same day, and which
+    /// can only be played once a day.
+    Daily,
+}
+
+impl GameMode {
+    /// This function serves as a means of returning the string representation of the enumeration.
+    pub(crate) const fn repr(self) -> &'static str {
+        match self {
+            Self::OneShot => "One shot",
+            Self::Classic => "Higher or lower",
+            Self::Daily => "Daily challenge",
+        }
+    }
+
+    /// This function returns whether the mode is played over several guesses with a hint after each
+    /// wrong one, as the classic mode and the daily challenge are.
+    pub(crate) const fn is_classic(self) -> bool {
+        matches!(self, Self::Classic | Self::Daily)
+    }
+}
+
+/// This enumeration holds information about the hints given to the user after a wrong guess in the
+/// classic game mode.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Hint {
+    /// This variant refers to the number being higher than the guess of the user.
+    Higher,
+    /// This variant refers to the number being lower than the guess of the user.
+    Lower,
+}
+
+impl Hint {
+    /// This function serves as a means of returning the string representation of the enumeration.
+    pub(crate) const fn repr(self) -> &'static str {
+        match self {
+            Self::Higher => "Higher",
+            Self::Lower => "Lower",
+        }
+    }
+}
+
+/// This structure holds the state of a round of the classic game mode, which spans several guesses.
+pub(crate) struct Classic {
+    /// This field refers to the number drawn for the round.
+    secret: Number,
+    /// This field refers to the lower and upper bounds of the range the number was drawn from.
+    range: (Number, Number),
+    /// This field refers to the amount of guesses made so far.
+    attempts: u32,
+    /// This field refers to the amount of guesses the user is given.
+    max_attempts: u32,
+    /// This field refers to the hint given after the last guess, if it was wrong.
+    hint: Option<Hint>,
+}
+
+impl Classic {
+    /// This function starts a new round of the classic game mode with the given number, drawn from
+    /// the given range, and the given amount of guesses.
+    pub(crate) const fn new(secret: Number, range: (Number, Number), max_attempts: u32) -> Self {
+        Self {
+            secret,
+            range,
+            attempts: 0,
+            max_attempts,
+            hint: None,
+        }
+    }
+
+    /// This function accounts for the given guess, and returns the hint for it if it was wrong.
+    pub(crate) fn guess(&mut self, guess: Number) -> Option<Hint> {
+        self.attempts += 1;
+        self.hint = match guess.cmp(&self.secret) {
+            Ordering::Less => Some(Hint::Higher),
+            Ordering::Greater => Some(Hint::Lower),
+            Ordering::Equal => None,
+        };
+
+        self.hint
+    }
+
+    /// This function returns whether the user has run out of guesses.
+    pub(crate) const fn is_over(&self) -> bool {
+        self.attempts >= self.max_attempts
+    }
+
+    /// This function returns the currently stored value in the [`struct@Classic::field@secret`]
+    /// field of the structure.
+    pub(crate) const fn secret(&self) -> Number {
+        self.secret
+    }
+
+    /// This function returns the currently stored value in the [`struct@Classic::field@range`] field
+    /// of the structure.
+    pub(crate) const fn range(&self) -> (Number, Number) {
+        self.range
+    }
+
+    /// This function returns the currently stored value in the [`struct@Classic::field@attempts`]
+    /// field of the structure.
+    pub(crate) const fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@Classic::field@max_attempts`] field of the structure.
+    pub(crate) const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// This function returns the currently stored value in the [`struct@Classic::field@hint`] field
+    /// of the structure.
+    pub(crate) const fn hint(&self) -> Option<Hint> {
+        self.hint
+    }
+}
+
+/// This enumeration holds information about the overlays that may be drawn on top of any screen.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Overlay {
+    /// This variant refers to the overlay with details useful to debug the game, such as the seed
+    /// of the RNG the numbers are drawn with.
+    Debug,
+}
+
+/// This enumeration holds information about the screens from which the model menu may be entered.
+#[derive(PartialEq, Clone, Copy)]
+pub(crate) enum MenuOrigin {
+    /// This variant refers to the model menu having been entered from the options menu.
+    OptionsMenu,
+    /// This variant refers to the model menu having been entered from the error popup, in which
+    /// case the request is retried with the newly selected model.
+    ErrorPopup,
 }
 
 /// This enumeration holds information about the different selectable items in the main menu.
@@ -46,6 +261,9 @@ pub(crate) enum Screen {
 pub(crate) enum MainMenuItem {
     /// This variant refers to the option to pick "Play" in the menu, and start the game.
     Play,
+    /// This variant refers to the option to pick "Statistics" in the menu, and enter the statistics
+    /// screen.
+    Statistics,
     /// This variant refers to the option to pick "Options" in the menu, and enter the options menu.
     Options,
     /// This variant refers to the option to pick "Exti" in the menu, and end the game.
@@ -58,11 +276,39 @@ pub(crate) enum OptionsMenuItem {
     /// This variant refers to the option to pick "Model" in the menu, and enter the model menu
     /// screen.
     Model,
+    /// This variant refers to the option to pick "Persona" in the menu, and switch to the next
+    /// persona.
+    Persona,
+    /// This variant refers to the option to pick "Difficulty" in the menu, and switch to the next
+    /// difficulty.
+    Difficulty,
+    /// This variant refers to the option to pick "Sampling" in the menu, and enter the sampling menu
+    /// screen.
+    Sampling,
     /// This variant refers to the option to pick "Return" in the menu, and return to the previous
     /// screen.
     Return,
 }
 
+/// This enumeration holds information about the items to be found in the sampling menu.
+#[derive(PartialEq)]
+pub(crate) enum SamplingMenuItem {
+    /// This variant refers to the option to pick "Temperature" in the menu, and switch to the next
+    /// temperature.
+    Temperature,
+    /// This variant refers to the option to pick "Top-p" in the menu, and switch to the next nucleus
+    /// sampling threshold.
+    TopP,
+    /// This variant refers to the option to pick "Max tokens" in the menu, and switch to the next
+    /// maximum amount of tokens.
+    MaxTokens,
+    /// This variant refers to the option to pick "Seed" in the menu, and switch to the next seed.
+    Seed,
+    /// This variant refers to the option to pick "Return" in the menu, and return to the options
+    /// menu.
+    Return,
+}
+
 /// This enumeration holds information about the possible states in which the in-game experience may
 /// be found.
 #[derive(PartialEq)]
@@ -73,6 +319,9 @@ pub(crate) enum GameScreen {
     /// This variant refers to the state of being in the end menu, with the result and a prompt to
     /// repeat for another game.
     EndMenu(EndMenuItem),
+    /// This variant refers to the state of being in the error popup, with the reason why the chat
+    /// completion request failed and a prompt on how to carry on.
+    ErrorPopup(ErrorPopupItem),
 }
 
 /// This enumeration holds information about the selectable prompts in the in-game menu.
@@ -95,9 +344,24 @@ pub(crate) enum EndMenuItem {
     Exit,
 }
 
+/// This enumeration holds information about the selectable items in the in-game error popup.
+#[derive(PartialEq)]
+pub(crate) enum ErrorPopupItem {
+    /// This variant refers to the option to pick "Retry" in the popup, and perform the request
+    /// again.
+    Retry,
+    /// This variant refers to the option to pick "Switch model" in the popup, and enter the model
+    /// menu before performing the request again.
+    SwitchModel,
+    /// This variant refers to the option to pick "Offline reply" in the popup, and carry on with a
+    /// reply from the built-in phrase bank.
+    Offline,
+}
+
 /// This enumeration holds information about the possible results obtained by the user after
 /// guessing a random number, and computing one from the their input range.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum RandomResult {
     /// This variant represents the state of having guessed the number correctly.
     Correct,
@@ -105,37 +369,325 @@ pub(crate) enum RandomResult {
     Incorrect,
 }
 
+/// This structure holds information about a round that has just been played. It is not sent as is
+/// to the language model, but it's kept along the request body for backends that build their own
+/// replies out of it.
+#[derive(Clone, Copy)]
+pub(crate) struct Round {
+    /// This field refers to whether the user guessed the number correctly.
+    result: RandomResult,
+    /// This field refers to the guess of the user.
+    guess: Number,
+    /// This field refers to the number drawn in the round.
+    secret: Number,
+    /// This field refers to the lower bound of the range input by the user, which is inclusive.
+    start: Number,
+    /// This field refers to the upper bound of the range input by the user, which is inclusive.
+    end: Number,
+    /// This field refers to the amount of rounds guessed correctly in a row, including this one.
+    streak: u32,
+    /// This field refers to the score of the user before this round.
+    score: u32,
+    /// This field refers to the game mode the round was played in.
+    mode: GameMode,
+    /// This field refers to the amount of guesses made in the round.
+    attempts: u32,
+    /// This field refers to the amount of guesses the user was given in the round.
+    max_attempts: u32,
+    /// This field refers to the hint to give the user, if the round is not over yet.
+    hint: Option<Hint>,
+}
+
+impl Round {
+    /// This function builds the summary of a round out of its result, the guess of the user, the
+    /// number drawn, the bounds of the range they input, their current streak and their score before
+    /// the round.
+    pub(crate) const fn new(
+        result: RandomResult,
+        guess: Number,
+        secret: Number,
+        (start, end): (Number, Number),
+        streak: u32,
+        score: u32,
+    ) -> Self {
+        Self {
+            result,
+            guess,
+            secret,
+            start,
+            end,
+            streak,
+            score,
+            mode: GameMode::OneShot,
+            attempts: 1,
+            max_attempts: 1,
+            hint: None,
+        }
+    }
+
+    /// This function marks the round as played in the given game mode, played as the classic one,
+    /// with the given amount of guesses made out of those given, and the hint to give the user if
+    /// the round is not over yet.
+    pub(crate) const fn classic(
+        mut self,
+        mode: GameMode,
+        attempts: u32,
+        max_attempts: u32,
+        hint: Option<Hint>,
+    ) -> Self {
+        self.mode = mode;
+        self.attempts = attempts;
+        self.max_attempts = max_attempts;
+        self.hint = hint;
+        self
+    }
+
+    /// This function returns the word the language model is told about the round with; namely,
+    /// whether the guess was correct or incorrect, or the hint if the round is not over yet.
+    pub(crate) const fn verdict(&self) -> &'static str {
+        match (self.hint, self.result) {
+            (Some(hint), _) => hint.repr(),
+            (None, RandomResult::Correct) => "Correct",
+            (None, RandomResult::Incorrect) => "Incorrect",
+        }
+    }
+
+    /// This function returns the summary of the round the language model is told about. The number
+    /// drawn is left out while the round is not over yet, as the language model would otherwise
+    /// give it away.
+    pub(crate) fn summary(&self) -> String {
+        let mut lines = vec![
+            format!("Result: {}", self.verdict()),
+            format!("Range: {}..={}", self.start, self.end),
+            format!("Guess: {}", self.guess),
+        ];
+        if self.hint.is_none() {
+            lines.push(format!("Number drawn: {}", self.secret));
+            lines.push(format!("Off by: {}", self.guess.abs_diff(self.secret)));
+        }
+        if self.mode.is_classic() {
+            lines.push(format!(
+                "Attempt: {} of {}",
+                self.attempts, self.max_attempts
+            ));
+        }
+        lines.push(format!("Streak: {}", self.streak));
+        lines.push(format!("Score: {}", self.score + self.points()));
+
+        lines.join("\n")
+    }
+
+    /// This function returns the amount of points the round is worth. A correct guess is worth a
+    /// point, plus another one for each guess left unused.
+    pub(crate) const fn points(&self) -> u32 {
+        match self.result {
+            RandomResult::Correct => self.max_attempts.saturating_sub(self.attempts) + 1,
+            RandomResult::Incorrect => 0,
+        }
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@result`] field
+    /// of the structure.
+    pub(crate) const fn result(&self) -> RandomResult {
+        self.result
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@guess`] field
+    /// of the structure.
+    pub(crate) const fn guess(&self) -> Number {
+        self.guess
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@secret`] field
+    /// of the structure.
+    pub(crate) const fn secret(&self) -> Number {
+        self.secret
+    }
+
+    /// This function returns the currently stored values in the [`struct@Round::field@start`] and
+    /// [`struct@Round::field@end`] fields of the structure.
+    pub(crate) const fn range(&self) -> (Number, Number) {
+        (self.start, self.end)
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@streak`] field
+    /// of the structure.
+    pub(crate) const fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@score`] field
+    /// of the structure.
+    pub(crate) const fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@mode`] field of
+    /// the structure.
+    pub(crate) const fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@attempts`]
+    /// field of the structure.
+    pub(crate) const fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// This function returns the currently stored value in the [`struct@Round::field@hint`] field of
+    /// the structure.
+    pub(crate) const fn hint(&self) -> Option<Hint> {
+        self.hint
+    }
+}
+
 /// This structure holds information about the request body to build for the chat completion request
 /// to use with the OpenRouter API.
 #[derive(Serialize)]
 pub(crate) struct Request {
     /// This field contains the language model to be used in the request.
     model: String,
+    /// This field contains the language models OpenRouter routes the request to, in order, should
+    /// the ones before them fail. It is left out of the request body when the server is not asked
+    /// to route the request.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    models: Vec<String>,
+    /// This field contains how OpenRouter routes the request among the language models in the
+    /// [`struct@Request::field@models`] field. It is left out of the request body along with them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    route: Option<&'static str>,
+    /// This field contains the language models the request is made with next, in order, should the
+    /// one in use fail. It is left empty when the server routes the request to them itself, so that
+    /// they're not requested twice.
+    #[serde(skip)]
+    fallbacks: Vec<String>,
     /// This field contains the vector of messages to provide to the language model.
     messages: Vec<Message>,
+    /// This field contains the sampling parameters of the reply, which are left out of the request
+    /// body when not set.
+    #[serde(flatten)]
+    sampling: Sampling,
+    /// This field contains whether the response should be delivered incrementally as server-sent
+    /// events. It is left out of the request body when not set.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    /// This field contains the options of the streamed response, which is asked to end with the
+    /// usage of the request. It is left out of the request body when not streaming.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<StreamOptions>,
+    /// This field contains the summary of the round the request is made for.
+    #[serde(skip)]
+    round: Round,
+    /// This field contains the identifier of the persona the reply is to be given as, for backends
+    /// that don't go by the system prompt.
+    #[serde(skip)]
+    persona: String,
 }
 
 impl Request {
-    /// This function serves as a request-body builder for the chat completion request, depending on
-    /// whether the request is to be made for a correct guess or otherwise an incorrect guess.
-    pub(crate) fn new(model: String, result: RandomResult) -> Self {
-        match result {
-            RandomResult::Correct => Self {
-                model,
-                messages: vec![
-                    Message::new(Role::System, LLM_INPUT.to_owned()),
-                    Message::new(Role::User, "Correct".to_owned()),
-                ],
-            },
-            RandomResult::Incorrect => Self {
-                model,
-                messages: vec![
-                    Message::new(Role::System, LLM_INPUT.to_owned()),
-                    Message::new(Role::User, "Incorrect".to_owned()),
-                ],
-            },
+    /// This function serves as a request-body builder for the chat completion request, telling the
+    /// language model about the given round, and depending on whether the response should be
+    /// streamed. The given system prompt is sent first, followed by the given messages of earlier
+    /// rounds for the language model to remember, and the given message telling it about the round.
+    pub(crate) fn new(
+        model: String,
+        round: Round,
+        stream: bool,
+        system_prompt: String,
+        memory: Vec<Message>,
+        user_prompt: String,
+    ) -> Self {
+        let mut messages = vec![Message::new(Role::System, system_prompt)];
+        messages.extend(memory);
+        messages.push(Message::new(Role::User, user_prompt));
+
+        Self {
+            model,
+            models: Vec::new(),
+            route: None,
+            fallbacks: Vec::new(),
+            messages,
+            sampling: Sampling::default(),
+            stream,
+            stream_options: stream.then_some(StreamOptions {
+                include_usage: true,
+            }),
+            round,
+            persona: String::new(),
+        }
+    }
+
+    /// This function sets the identifier of the persona the reply is to be given as.
+    pub(crate) fn with_persona(mut self, persona: String) -> Self {
+        self.persona = persona;
+        self
+    }
+
+    /// This function sets the language models the request is made with next, in order, should the
+    /// one in use fail. If the server is to route the request, they're sent along with the request
+    /// for the server to try them in turn instead.
+    pub(crate) fn with_fallbacks(mut self, fallbacks: Vec<String>, route: bool) -> Self {
+        if route && !fallbacks.is_empty() {
+            self.route = Some("fallback");
+            self.models = std::iter::once(self.model.clone())
+                .chain(fallbacks)
+                .collect();
+        } else {
+            self.fallbacks = fallbacks;
+        }
+
+        self
+    }
+
+    /// This function sets the sampling parameters of the reply.
+    pub(crate) const fn with_sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// This function switches the request to the next of the fallback models, if there's any left,
+    /// and returns whether it did so.
+    pub(crate) fn fall_back(&mut self) -> bool {
+        if self.fallbacks.is_empty() {
+            return false;
         }
+
+        self.model = self.fallbacks.remove(0);
+
+        true
+    }
+
+    /// This function returns the currently stored value in the [`struct@Request::field@model`]
+    /// field of the structure.
+    pub(crate) fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// This function returns the currently stored value in the [`struct@Request::field@stream`]
+    /// field of the structure.
+    pub(crate) const fn stream(&self) -> bool {
+        self.stream
+    }
+
+    /// This function returns the currently stored value in the [`struct@Request::field@round`]
+    /// field of the structure.
+    pub(crate) const fn round(&self) -> &Round {
+        &self.round
     }
+
+    /// This function returns the currently stored value in the [`struct@Request::field@persona`]
+    /// field of the structure.
+    pub(crate) fn persona(&self) -> &str {
+        &self.persona
+    }
+}
+
+/// This structure holds information about the options of a streamed chat completion request.
+#[derive(Serialize)]
+pub(crate) struct StreamOptions {
+    /// This field refers to whether the stream ends with an event carrying the usage of the
+    /// request.
+    include_usage: bool,
 }
 
 /// This structure holds information about the object type to use for each of the messages in the
@@ -151,7 +703,7 @@ pub(crate) struct Message {
 impl Message {
     /// This function serves as a small utility to build messages based on a given role and a string
     /// message. It is used in the request body builder function [`Request::new`].
-    const fn new(role: Role, content: String) -> Self {
+    pub(crate) const fn new(role: Role, content: String) -> Self {
         Self { role, content }
     }
 
@@ -181,15 +733,40 @@ pub(crate) enum Role {
 pub(crate) struct ChatCompletionResponse {
     /// This field refers to the array of messages the language model may have produced in its
     /// response.
+    #[serde(default)]
     choices: Vec<Choices>,
+    /// This field refers to the language model that produced the response, which may be a fallback
+    /// model the server routed the request to.
+    model: Option<String>,
+    /// This field refers to the amount of tokens the request took up and what it cost, if reported.
+    usage: Option<Usage>,
+    /// This field refers to the error reported by the provider in place of the messages, if any.
+    error: Option<ErrorBody>,
 }
 
 impl ChatCompletionResponse {
+    /// This function returns the currently stored value in the
+    /// [`struct@ChatCompletionResponse::field@usage`] field of the structure.
+    pub(crate) const fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@ChatCompletionResponse::field@model`] field of the structure.
+    pub(crate) fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
     /// This function returns the currently stored value in the
     /// [`struct@ChatCompletionResponse::field@choices`] field of the structure.
     pub(crate) const fn choices(&self) -> &Vec<Choices> {
         &self.choices
     }
+
+    /// This function takes the error reported by the provider out of the response, if any.
+    pub(crate) const fn take_error(&mut self) -> Option<ErrorBody> {
+        self.error.take()
+    }
 }
 
 /// This structure holds information about the specific dummy object used as part of the chat
@@ -208,22 +785,275 @@ impl Choices {
     }
 }
 
+/// This structure holds information about each of the server-sent events received as part of a
+/// streamed chat completion request to the OpenRouter API.
+#[derive(Deserialize)]
+pub(crate) struct ChatCompletionChunk {
+    /// This field refers to the array of partial messages the language model may have produced in
+    /// this event.
+    #[serde(default)]
+    choices: Vec<ChunkChoices>,
+    /// This field refers to the language model producing the response, which may be a fallback model
+    /// the server routed the request to.
+    model: Option<String>,
+    /// This field refers to the amount of tokens the request took up and what it cost, which is
+    /// only reported in the last event.
+    usage: Option<Usage>,
+    /// This field refers to the error reported by the provider in the middle of the stream, if any.
+    error: Option<ErrorBody>,
+}
+
+impl ChatCompletionChunk {
+    /// This function returns the currently stored value in the
+    /// [`struct@ChatCompletionChunk::field@usage`] field of the structure.
+    pub(crate) const fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@ChatCompletionChunk::field@model`] field of the structure.
+    pub(crate) fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// This function consumes the event and returns the content carried by its last partial
+    /// message, if any, or the error reported by the provider in its place.
+    pub(crate) fn into_content(self) -> Result<Option<String>, ApiError> {
+        match self.error {
+            Some(error) => Err(ApiError::Provider(error)),
+            None => Ok(self
+                .choices
+                .into_iter()
+                .last()
+                .and_then(|choice| choice.delta.content)),
+        }
+    }
+}
+
+/// This structure holds information about the specific dummy object used as part of each
+/// server-sent event for either one of the partial messages returned by the language model.
+#[derive(Deserialize)]
+pub(crate) struct ChunkChoices {
+    /// This field refers to the piece of the response carried by the event.
+    delta: Delta,
+}
+
+/// This structure holds information about the piece of the response carried by each server-sent
+/// event.
+#[derive(Deserialize)]
+pub(crate) struct Delta {
+    /// This field refers to the text to append to the response. Some events carry no text at all,
+    /// so it's wrapped in an [`Option`].
+    content: Option<String>,
+}
+
+/// This structure holds information about the amount of tokens taken up by one or more chat
+/// completion requests, and what they cost.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub(crate) struct Usage {
+    /// This field refers to the amount of tokens of the prompt.
+    #[serde(default)]
+    prompt_tokens: u64,
+    /// This field refers to the amount of tokens of the reply.
+    #[serde(default)]
+    completion_tokens: u64,
+    /// This field refers to the cost in US dollars, if known. OpenRouter reports it along with the
+    /// amount of tokens, while other servers only report the latter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cost: Option<f64>,
+    /// This field refers to the amount of requests whose cost is unknown, which are left out of the
+    /// cost.
+    #[serde(default, skip_serializing_if = "Usage::is_zero")]
+    unpriced: u32,
+}
+
+impl Usage {
+    /// This function returns the usage with its cost estimated out of the given price of the prompt
+    /// and the completion, in US dollars per million tokens, unless it's known already.
+    pub(crate) fn or_estimate(self, prices: Option<(f64, f64)>) -> Self {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "The amount of tokens of a request is nowhere near the precision of a float."
+        )]
+        let estimate = |(prompt, completion): (f64, f64)| {
+            (self.prompt_tokens as f64).mul_add(prompt, self.completion_tokens as f64 * completion)
+                / 1_000_000.0
+        };
+
+        let cost = self.cost.or_else(|| prices.map(estimate));
+
+        Self {
+            cost,
+            unpriced: u32::from(cost.is_none()),
+            ..self
+        }
+    }
+
+    /// This function adds the given usage to this one. The cost is the sum of those known, and the
+    /// requests whose cost is unknown are counted apart, so that the cost is only a lower bound
+    /// while there's any of them.
+    pub(crate) fn add(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cost = match (self.cost, other.cost) {
+            (Some(cost), Some(other)) => Some(cost + other),
+            (cost, other) => cost.or(other),
+        };
+        self.unpriced += other.unpriced;
+    }
+
+    /// This function returns whether the cost is a lower bound, as the cost of some of the requests
+    /// is unknown.
+    pub(crate) const fn is_lower_bound(self) -> bool {
+        self.unpriced > 0
+    }
+
+    /// This function returns whether the given amount of requests is zero, so that it's left out of
+    /// the history file.
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "Serde passes the fields to skip by reference."
+    )]
+    const fn is_zero(amount: &u32) -> bool {
+        *amount == 0
+    }
+
+    /// This function returns the amount of tokens of both the prompt and the reply.
+    pub(crate) const fn tokens(self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// This function returns the currently stored value in the [`struct@Usage::field@cost`] field of
+    /// the structure.
+    pub(crate) const fn cost(self) -> Option<f64> {
+        self.cost
+    }
+}
+
 /// This structure holds information about the response received as part of the model list request
 /// to the OpenRouter API.
 #[derive(Deserialize)]
 pub(crate) struct ModelListResponse {
     /// This field contains information about the data held by the entire API model list. Within it
     /// are the details of each model.
-    data: Vec<Data>,
+    data: Vec<ModelInfo>,
+}
+
+impl ModelListResponse {
+    /// This function consumes the response and returns the details of each of the models in the
+    /// list.
+    pub(crate) fn into_models(self) -> Vec<ModelInfo> {
+        self.data
+    }
 }
 
 /// This structure holds information about each specific model available through the OpenRouter API
-/// to be received as a response to the model list request.
-#[derive(Deserialize)]
-pub(crate) struct Data {
-    /// This field refers to the single element from the model list that this project is intered in;
-    /// the codename the model receives.
+/// to be received as a response to the model list request. Other OpenAI-compatible servers may only
+/// list the identifier of each model, so every other detail is optional.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct ModelInfo {
+    /// This field refers to the codename the model receives, which is used to select it.
     id: String,
+    /// This field refers to the public-facing name of the model.
+    #[serde(default)]
+    name: String,
+    /// This field refers to the description of the model given by the provider.
+    #[serde(default)]
+    description: String,
+    /// This field refers to the maximum amount of tokens the model can take in, if known.
+    #[serde(default)]
+    context_length: Option<u64>,
+    /// This field refers to the price of using the model, if known.
+    #[serde(default)]
+    pricing: Option<Pricing>,
+}
+
+impl ModelInfo {
+    /// This function builds the details of a model with the given identifier, name and description,
+    /// which is free to use and has no context length to speak of.
+    pub(crate) fn new(id: &str, name: &str, description: &str) -> Self {
+        Self {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            description: description.to_owned(),
+            context_length: None,
+            pricing: Some(Pricing {
+                prompt: "0".to_owned(),
+                completion: "0".to_owned(),
+            }),
+        }
+    }
+
+    /// This function returns the currently stored value in the [`struct@ModelInfo::field@id`] field
+    /// of the structure.
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// This function returns the currently stored value in the [`struct@ModelInfo::field@name`]
+    /// field of the structure, or the identifier if the server gave no name.
+    pub(crate) fn name(&self) -> &str {
+        if self.name.is_empty() {
+            &self.id
+        } else {
+            &self.name
+        }
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@ModelInfo::field@description`] field of the structure.
+    pub(crate) fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// This function returns the currently stored value in the
+    /// [`struct@ModelInfo::field@context_length`] field of the structure.
+    pub(crate) const fn context_length(&self) -> Option<u64> {
+        self.context_length
+    }
+
+    /// This function returns the price of the prompt and the completion of the model, in US dollars
+    /// per million tokens, if known.
+    pub(crate) fn prices(&self) -> Option<(f64, f64)> {
+        let pricing = self.pricing.as_ref()?;
+
+        Some((
+            Pricing::per_million(&pricing.prompt)?,
+            Pricing::per_million(&pricing.completion)?,
+        ))
+    }
+
+    /// This function returns whether the model is free to use, either because the provider says so
+    /// in its identifier or because both its prompt and completion cost nothing.
+    pub(crate) fn is_free(&self) -> bool {
+        self.id.ends_with(":free")
+            || self
+                .prices()
+                .is_some_and(|(prompt, completion)| prompt == 0.0 && completion == 0.0)
+    }
+}
+
+/// This structure holds information about the price of using a model, as given by the OpenRouter
+/// API in US dollars per token.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Pricing {
+    /// This field refers to the price of each token of the prompt.
+    prompt: String,
+    /// This field refers to the price of each token of the completion.
+    completion: String,
+}
+
+impl Pricing {
+    /// This function parses the given price per token into a price per million tokens. Prices that
+    /// can't be parsed or that are negative, which the OpenRouter API uses for models whose price
+    /// varies, are unknown.
+    fn per_million(price: &str) -> Option<f64> {
+        price
+            .parse::<f64>()
+            .ok()
+            .filter(|price| *price >= 0.0)
+            .map(|price| price * 1_000_000.0)
+    }
 }
 
 /// This enumeration holds information about the type of menu that can be rendered in a similar
@@ -234,6 +1064,10 @@ pub(crate) enum MenuType {
     MainMenu(u8),
     /// This variant refers to the options menu in the game.
     OptionsMenu(u8),
+    /// This variant refers to the sampling menu in the game.
+    SamplingMenu(u8),
+    /// This variant refers to the game mode menu in the game.
+    GameMode(u8),
 }
 
 impl MenuType {
@@ -242,6 +1076,8 @@ impl MenuType {
         match *self {
             Self::MainMenu(_) => "Main menu",
             Self::OptionsMenu(_) => "Options menu",
+            Self::SamplingMenu(_) => "Sampling",
+            Self::GameMode(_) => "Game mode",
         }
     }
 }
@@ -254,6 +1090,37 @@ pub(crate) enum ModelMenuDirection {
     Up,
     /// This variant refers to the command of moving the viewport downward.
     Down,
+    /// This variant refers to the command of moving the viewport a page upward.
+    PageUp,
+    /// This variant refers to the command of moving the viewport a page downward.
+    PageDown,
+    /// This variant refers to the command of moving the viewport to the first model.
+    Top,
+    /// This variant refers to the command of moving the viewport to the last model.
+    Bottom,
+}
+
+/// This enumeration holds information about the sections the model menu is split into when there
+/// are favorite or recently used models to pin above the rest.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ModelSection {
+    /// This variant refers to the section of the models starred by the user.
+    Favorites,
+    /// This variant refers to the section of the models picked most recently.
+    Recent,
+    /// This variant refers to the section of every model listed.
+    All,
+}
+
+impl ModelSection {
+    /// This function returns the title the section is headed with in the model menu.
+    pub(crate) const fn title(self) -> &'static str {
+        match self {
+            Self::Favorites => "Favorites",
+            Self::Recent => "Recent",
+            Self::All => "All models",
+        }
+    }
 }
 
 /// This enumeration holds information about whether textual input should be handled as a deletion
@@ -269,6 +1136,13 @@ pub(crate) enum OperationType {
     SwitchFocus,
 }
 
+/// This constant holds the base URL of the OpenRouter API, which is the server used for the chat
+/// completion and model list requests unless told otherwise.
+pub(crate) const DEFAULT_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// This constant holds how many of the models picked most recently are pinned in the model menu.
+pub(crate) const RECENT_MODELS: usize = 5;
+
 /// This structure holds information useful to the command-line argument parser in use; namely,
 /// [`clap`].
 #[derive(Parser)]
@@ -278,27 +1152,236 @@ pub struct Cli {
     ///
     /// This should be set through the command-line, the environment variable or the in-game menu.
     /// If not setting it through the in-game menu, one must use the name in the OpenRouter model
-    /// page that appears right below the public-facing name.
+    /// page that appears right below the public-facing name. When using a different server through
+    /// the base URL, this must be one of the models that server lists.
+    #[arg(short, long, env = "OPENROUTER_MODEL", value_name = "MODEL_NAME")]
+    model: Option<String>,
+    /// A model to fall back to when the selected one fails.
+    ///
+    /// May be given more than once, in which case the models are tried in the order given. When
+    /// the selected model is down, or keeps failing after every attempt, the request is made again
+    /// with the next model. Through the OpenRouter API, the models are sent along with the request
+    /// instead, for OpenRouter to route it to them.
     #[arg(
-        short,
-        long,
-        env = "OPENROUTER_MODEL",
+        long = "fallback-model",
+        env = "RANDY_FALLBACK_MODELS",
         value_name = "MODEL_NAME",
-        requires = "api_key",
-        value_parser = verify_models
+        value_delimiter = ','
     )]
-    model: Option<String>,
+    fallback_models: Vec<String>,
     /// The OpenRouter API key to use for the AI request.
     ///
-    /// This should be set through the command-line or the environment variable. It is required to
-    /// successfully perform the chat completion request to the OpenRouter API.
+    /// This should be set through the command-line, the environment variable or a file pointed to
+    /// by the configuration file. It is required to successfully perform the chat completion request
+    /// to the OpenRouter API, but it may be left out when talking to a local server through the
+    /// base URL.
+    #[arg(long, env = "OPENROUTER_API_KEY", value_name = "YOUR_API_KEY")]
+    api_key: Option<String>,
+    /// The base URL of the OpenAI-compatible server to use for the AI request.
+    ///
+    /// Both the chat completion request and the model list request are performed against this URL,
+    /// so it can point to any OpenAI-compatible server (Ollama, llama.cpp, vLLM, ...). Defaults to
+    /// the OpenRouter API.
+    #[arg(long, env = "RANDY_BASE_URL", value_name = "URL")]
+    base_url: Option<String>,
+    /// Wait for the whole reply instead of streaming it.
+    ///
+    /// By default, the reply of the language model is streamed and rendered as it arrives. This
+    /// disables streaming for servers that don't support it.
+    #[arg(long = "no-stream", action = ArgAction::SetFalse)]
+    stream: bool,
+    /// Play without a language model.
+    ///
+    /// The replies are picked from a built-in phrase bank instead, so that neither an API key nor a
+    /// network connection are required. Even when not set, the phrase bank is used whenever the
+    /// request to the language model fails.
+    #[arg(long)]
+    offline: bool,
+    /// The maximum amount of attempts at the AI request.
+    ///
+    /// Requests failing because of rate limits or providers being down are retried with an
+    /// exponential backoff up to this amount of attempts, honoring the delay asked for by the
+    /// server if any.
+    #[arg(
+        long,
+        env = "RANDY_MAX_ATTEMPTS",
+        value_name = "ATTEMPTS",
+        default_value_t = 4,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    max_attempts: u32,
+    /// The difficulty of the game.
+    ///
+    /// The difficulty determines the range the range prompt is filled with, unless a default range
+    /// is set in the configuration file. It can also be changed through the in-game options menu.
+    #[arg(long, env = "RANDY_DIFFICULTY", value_name = "DIFFICULTY")]
+    difficulty: Option<Difficulty>,
+    /// How the hints of the higher or lower game mode are given.
+    ///
+    /// The hints may be given plainly, or voiced by the language model, in which case a request is
+    /// performed after every wrong guess.
+    #[arg(long, env = "RANDY_HINTS", value_name = "STYLE")]
+    hints: Option<HintStyle>,
+    /// The amount of earlier rounds the language model remembers.
+    ///
+    /// The rounds played in the session and the replies given to them are sent along with each
+    /// request, so that the language model can refer back to them. Defaults to none.
+    #[arg(long, env = "RANDY_MEMORY", value_name = "ROUNDS")]
+    memory: Option<u32>,
+    /// The estimated maximum amount of tokens the remembered rounds may take up.
+    ///
+    /// The oldest rounds are left out of the request when the estimate goes past this amount.
+    #[arg(long, env = "RANDY_MEMORY_TOKENS", value_name = "TOKENS")]
+    memory_tokens: Option<u32>,
+    /// The persona the language model speaks as.
+    ///
+    /// Built-in personas are `cowboy`, `pirate`, `noir-detective` and `drill-sergeant`. More of them
+    /// can be defined in TOML files under the `personas` directory next to the configuration file,
+    /// and selected by the name of their file. It can also be changed through the in-game options
+    /// menu.
+    #[arg(long, env = "RANDY_PERSONA", value_name = "PERSONA")]
+    persona: Option<String>,
+    /// The name you go by.
+    ///
+    /// It takes the place of the `{player}` variable in the system prompt and the user prompt
+    /// templates. Defaults to "partner".
+    #[arg(long, env = "RANDY_PLAYER", value_name = "NAME")]
+    player: Option<String>,
+    /// The temperature of the replies, from 0 to 2.
+    ///
+    /// Lower temperatures make for more predictable replies. Defaults to that of the model. It can
+    /// also be changed through the in-game sampling menu, as can the other sampling parameters.
+    #[arg(
+        long,
+        env = "RANDY_TEMPERATURE",
+        value_name = "TEMPERATURE",
+        value_parser = Sampling::parse_temperature
+    )]
+    temperature: Option<f64>,
+    /// The nucleus sampling threshold of the replies, from 0 to 1.
+    ///
+    /// Only the most likely tokens adding up to this probability are sampled from. Defaults to that
+    /// of the model.
+    #[arg(
+        long,
+        env = "RANDY_TOP_P",
+        value_name = "TOP_P",
+        value_parser = Sampling::parse_top_p
+    )]
+    top_p: Option<f64>,
+    /// The maximum amount of tokens of the replies.
+    ///
+    /// Replies are cut short once they reach this amount of tokens. Defaults to that of the model.
+    #[arg(long, env = "RANDY_MAX_TOKENS", value_name = "TOKENS")]
+    max_tokens: Option<u32>,
+    /// The seed the replies are sampled with.
+    ///
+    /// The same round gets the same reply when sampled with the same seed, which comes in handy
+    /// when trying out prompts. Only some models support it.
+    #[arg(long, env = "RANDY_SAMPLING_SEED", value_name = "SEED")]
+    sampling_seed: Option<u64>,
+    /// The most the session may spend on replies, in US dollars.
+    ///
+    /// Once the replies of the session have cost more than this, the rest of them are picked from
+    /// the built-in phrase bank instead. The cost is taken from the server if reported, and
+    /// estimated out of the price of the model otherwise.
     #[arg(
         long,
-        env = "OPENROUTER_API_KEY",
-        value_name = "YOUR_API_KEY",
-        required = true
+        env = "RANDY_SPEND_CAP",
+        value_name = "DOLLARS",
+        value_parser = Config::parse_spend_cap
     )]
-    api_key: String,
+    spend_cap: Option<f64>,
+    /// The seed of the RNG the numbers of the game are drawn with.
+    ///
+    /// The same seed draws the same numbers for the same guesses, so that a session can be played
+    /// again exactly. Defaults to a random seed, which is shown in the debug overlay (F12) and kept
+    /// in the statistics history along with each round.
+    #[arg(long, env = "RANDY_SEED", value_name = "SEED")]
+    seed: Option<u64>,
+    /// The path to the configuration file.
+    ///
+    /// Defaults to `randy-ng/config.toml` under `$XDG_CONFIG_HOME`, or under `$HOME/.config` if the
+    /// former is not set. Settings given through the command-line or the environment take
+    /// precedence over those in the file.
+    #[arg(long, env = "RANDY_CONFIG", value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// A command to run instead of the interactive game.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// This enumeration holds information about the commands that may be run from the command-line
+/// instead of the interactive game, for scripting purposes. Each of them prints its outcome and
+/// exits right away.
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Play a single round and print its result along with the reply.
+    ///
+    /// The round is played in the one-shot game mode and kept in the statistics history, as those
+    /// played in the interactive game are.
+    Play {
+        /// The range the number is drawn from, such as 1..=100 or -50..50.
+        ///
+        /// As in Rust, n..=m includes m and n..m doesn't. Defaults to the range the range prompt is
+        /// filled with in the interactive game.
+        #[arg(long, value_name = "RANGE", allow_hyphen_values = true)]
+        range: Option<String>,
+        /// The guess, which must be in the range.
+        #[arg(long, value_name = "NUMBER", allow_hyphen_values = true)]
+        guess: String,
+        /// Print the round as a JSON object instead of plain text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the models available, optionally filtered.
+    ///
+    /// The list is taken from the cache while it's fresh, as it is in the model menu.
+    Models {
+        /// Only list the models matching this query, from the closest match to the farthest.
+        ///
+        /// The characters of the query must appear in the identifier of the model in the same
+        /// order, though not necessarily next to each other.
+        query: Option<String>,
+        /// Only list the models free to use.
+        #[arg(long)]
+        free: bool,
+        /// Only list the models whose prompt and completion prices are at most this many US
+        /// dollars per million tokens.
+        #[arg(long, value_name = "DOLLARS")]
+        max_price: Option<f64>,
+        /// Print the models as a JSON array instead of plain text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Fetch the reply to a made-up round with the given result, without playing it.
+    ///
+    /// The round is not kept in the statistics history.
+    Ask {
+        /// The result of the round.
+        #[arg(long, value_name = "RESULT")]
+        result: RandomResult,
+        /// The range of the round, such as 1..=100 or -50..50.
+        ///
+        /// As in Rust, n..=m includes m and n..m doesn't. Defaults to the range the range prompt is
+        /// filled with in the interactive game.
+        #[arg(long, value_name = "RANGE", allow_hyphen_values = true)]
+        range: Option<String>,
+        /// The guess of the round, which must be in the range.
+        ///
+        /// Defaults to a number drawn from the range.
+        #[arg(long, value_name = "NUMBER", allow_hyphen_values = true)]
+        guess: Option<String>,
+        /// Print the round as a JSON object instead of plain text.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the summary of the daily challenge of the day, to be shared once it's over.
+    Daily {
+        /// Print the challenge as a JSON object instead of plain text.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 impl Cli {
@@ -308,47 +1391,240 @@ impl Cli {
         self.model.as_ref()
     }
 
+    /// This function returns the currently stored value of the
+    /// [`struct@Cli::field@fallback_models`] field in the structure.
+    pub(crate) fn fallback_models(&self) -> &[String] {
+        &self.fallback_models
+    }
+
     /// This function returns the currently stored value of the [`struct@Cli::field@api_key`] field
     /// in the structure.
-    pub(crate) const fn api_key(&self) -> &String {
-        &self.api_key
+    pub(crate) const fn api_key(&self) -> Option<&String> {
+        self.api_key.as_ref()
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@base_url`] field
+    /// in the structure.
+    pub(crate) const fn base_url(&self) -> Option<&String> {
+        self.base_url.as_ref()
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@stream`] field in
+    /// the structure.
+    pub(crate) const fn stream(&self) -> bool {
+        self.stream
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@offline`] field
+    /// in the structure.
+    pub(crate) const fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@max_attempts`]
+    /// field in the structure.
+    pub(crate) const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@difficulty`]
+    /// field in the structure.
+    pub(crate) const fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@persona`] field
+    /// in the structure.
+    pub(crate) const fn persona(&self) -> Option<&String> {
+        self.persona.as_ref()
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@player`] field in
+    /// the structure.
+    pub(crate) const fn player(&self) -> Option<&String> {
+        self.player.as_ref()
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@spend_cap`]
+    /// field in the structure.
+    pub(crate) const fn spend_cap(&self) -> Option<f64> {
+        self.spend_cap
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@seed`] field in
+    /// the structure.
+    pub(crate) const fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// This function returns the sampling parameters given through the command-line, out of the
+    /// [`struct@Cli::field@temperature`], [`struct@Cli::field@top_p`],
+    /// [`struct@Cli::field@max_tokens`] and [`struct@Cli::field@sampling_seed`] fields in the
+    /// structure.
+    pub(crate) const fn sampling(&self) -> Sampling {
+        Sampling::new(
+            self.temperature,
+            self.top_p,
+            self.max_tokens,
+            self.sampling_seed,
+        )
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@hints`] field in
+    /// the structure.
+    pub(crate) const fn hints(&self) -> Option<HintStyle> {
+        self.hints
+    }
+
+    /// This function returns the currently stored value of the [`struct@Cli::field@memory`] field in
+    /// the structure.
+    pub(crate) const fn memory(&self) -> Option<u32> {
+        self.memory
     }
-}
 
-/// This function serves as a way of fetching the models currently available for use through the
-/// OpenRouter API. Note it does not require any type of authentication so the API key is not used.
-pub(crate) fn fetch_models() -> Result<Vec<String>> {
-    let response: ModelListResponse = ureq::get("https://openrouter.ai/api/v1/models")
-        .call()?
-        .into_body()
-        .read_json()?;
-    let mut output: Vec<String> = vec![];
+    /// This function returns the currently stored value of the
+    /// [`struct@Cli::field@memory_tokens`] field in the structure.
+    pub(crate) const fn memory_tokens(&self) -> Option<u32> {
+        self.memory_tokens
+    }
 
-    for model in response.data {
-        output.push(model.id);
+    /// This function returns the currently stored value of the [`struct@Cli::field@config`] field in
+    /// the structure.
+    pub(crate) const fn config(&self) -> Option<&PathBuf> {
+        self.config.as_ref()
     }
 
-    Ok(output)
+    /// This function returns the currently stored value of the [`struct@Cli::field@command`] field
+    /// in the structure.
+    pub(crate) const fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
 }
 
-/// This function serves as a value parser for the `model` field in the `Cli` structure. It is used
-/// by `clap` to check if the model input by the user is correct. To that extent, it fetches the
-/// list of models available for use through the OpenRouter API and checks that the input model is,
-/// indeed, one of these.
-fn verify_models(model: &str) -> Result<String> {
-    let list = fetch_models()?;
-    let mut output = String::new();
+#[cfg(test)]
+mod tests {
+    use color_eyre::Result;
+    use regex::Regex;
+
+    use serde_json::json;
+
+    use super::{parse_range, Number, RandomResult, Request, Round, RANGE_PATTERN};
+
+    /// This function parses the given range with the pattern the game uses.
+    fn range(text: &str) -> Result<(Number, Number)> {
+        parse_range(&Regex::new(RANGE_PATTERN)?, text)
+    }
+
+    /// This test checks that ranges with negative bounds are parsed.
+    #[test]
+    fn negative_bounds() {
+        assert_eq!(
+            range("-50..50").ok(),
+            Some((-50, 49)),
+            "the upper bound is left out"
+        );
+        assert_eq!(
+            range("-50..=-10").ok(),
+            Some((-50, -10)),
+            "both bounds are negative"
+        );
+    }
 
-    for elem in list {
-        if elem == model {
-            model.clone_into(&mut output);
-            break;
+    /// This test checks that the upper bound is only included with `..=`.
+    #[test]
+    fn exclusive_end() {
+        assert_eq!(range("1..10").ok(), Some((1, 9)), "1..10 ends at 9");
+        assert_eq!(range("1..=10").ok(), Some((1, 10)), "1..=10 ends at 10");
+        assert_eq!(range("1..3").ok(), Some((1, 2)), "1..3 holds two numbers");
+    }
+
+    /// This test checks that ranges of less than two numbers are rejected.
+    #[test]
+    fn too_few_numbers() {
+        for text in ["1..=1", "1..2", "1..1", "10..=1"] {
+            assert!(range(text).is_err(), "{text} holds less than two numbers");
         }
     }
 
-    if output.is_empty() {
-        Err(eyre!("invalid model"))
-    } else {
-        Ok(output)
+    /// This test checks that the smallest and largest numbers can be used as bounds.
+    #[test]
+    fn extreme_bounds() {
+        assert_eq!(
+            range(&format!("{}..0", Number::MIN)).ok(),
+            Some((Number::MIN, -1)),
+            "the smallest number is a valid lower bound"
+        );
+        assert_eq!(
+            range(&format!("{}..={}", Number::MIN, Number::MAX)).ok(),
+            Some((Number::MIN, Number::MAX)),
+            "the whole range of numbers is a valid range"
+        );
+        assert!(
+            range(&format!("0..{}", Number::MIN)).is_err(),
+            "nothing is below the smallest number"
+        );
+    }
+
+    /// This test checks that bounds that don't fit in a number are rejected, telling which way they
+    /// overflow.
+    #[test]
+    fn overflowing_bounds() {
+        assert!(
+            range("1..=170141183460469231731687303715884105728")
+                .is_err_and(|err| err.to_string().contains("too large")),
+            "the upper bound is one past the largest number"
+        );
+        assert!(
+            range("-170141183460469231731687303715884105729..=1")
+                .is_err_and(|err| err.to_string().contains("too small")),
+            "the lower bound is one past the smallest number"
+        );
+    }
+
+    /// This test checks that text that is not a range is rejected.
+    #[test]
+    fn malformed() {
+        for text in ["", "1..", "..10", "1...10", "+1..10", "1.5..10", "a..b"] {
+            assert!(range(text).is_err(), "{text:?} is not a range");
+        }
+    }
+
+    /// This test checks that the fallback models are either sent along with the request for the
+    /// server to route it to them, or tried in turn, but never both.
+    #[test]
+    fn routed_fallbacks() {
+        let request = || {
+            Request::new(
+                "a".to_owned(),
+                Round::new(RandomResult::Correct, 1, 1, (1, 2), 1, 0),
+                false,
+                String::new(),
+                Vec::new(),
+                String::new(),
+            )
+        };
+
+        let mut routed = request().with_fallbacks(vec!["b".to_owned()], true);
+        assert_eq!(
+            serde_json::to_value(&routed)
+                .ok()
+                .and_then(|body| body.get("models").cloned()),
+            Some(json!(["a", "b"])),
+            "the server is sent every model to route the request to"
+        );
+        assert!(!routed.fall_back(), "the server already tried every model");
+
+        let mut unrouted = request().with_fallbacks(vec!["b".to_owned()], false);
+        assert_eq!(
+            serde_json::to_value(&unrouted)
+                .ok()
+                .and_then(|body| body.get("models").cloned()),
+            None,
+            "the server is only sent the model in use"
+        );
+        assert!(
+            unrouted.fall_back() && unrouted.model() == "b",
+            "the next model is tried in turn"
+        );
     }
 }
@@ -0,0 +1,325 @@
+//! This module contains support for performing the chat completion request on a separate thread, so
+//! that the main loop of the application keeps drawing the TUI and handling input while the request
+//! is in flight. Transient failures are retried on that same thread following a [`RetryPolicy`].
+//! The list of models is refreshed in the background in the same fashion.
+
+use std::{
+    cmp,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use fastrand::Rng;
+
+use crate::{
+    error::ApiError,
+    llm::{ChatBackend, Reply},
+    utils::{ModelInfo, Request},
+};
+
+/// This constant holds the interval at which a worker thread waiting to retry checks whether its
+/// request has been cancelled.
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// This enumeration holds information about the messages the worker thread sends back to the main
+/// loop of the application.
+pub(crate) enum WorkerMessage {
+    /// This variant refers to a new attempt at the request being made, carrying its number with the
+    /// model in use, starting from one.
+    Attempt(u32),
+    /// This variant refers to the request being made with the given fallback model from then on,
+    /// as the model in use failed.
+    Fallback(String),
+    /// This variant refers to a piece of the message of the language model having arrived while
+    /// streaming the response.
+    Delta(String),
+    /// This variant refers to the request having finished, either with the message returned by the
+    /// language model or with the error that made it fail.
+    Done(Result<Reply, ApiError>),
+}
+
+/// This structure holds information about how a failed chat completion request is retried. Rate
+/// limits and providers being down are retried with an exponential backoff with jitter, unless the
+/// server says how long to wait for, which is still capped at the maximum delay. Empty messages are
+/// retried straight away, but only so many times. Once a model doesn't exist or is out of attempts,
+/// the request moves on to the next fallback model, if any, with its attempts starting over.
+#[derive(Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    /// This field refers to the maximum amount of attempts made when the request keeps failing with
+    /// a transient error.
+    max_attempts: u32,
+    /// This field refers to the maximum amount of times an empty message is retried.
+    max_empty_replies: u32,
+    /// This field refers to the delay before the first retry, which doubles with every retry.
+    base_delay: Duration,
+    /// This field refers to the maximum delay between two attempts.
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// This function builds a retry policy making at most the given amount of attempts, and using
+    /// the default values for everything else.
+    pub(crate) fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// This function computes how long to wait for before the attempt following the given one. The
+    /// delay is halved and the other half is drawn at random, so that clients rate limited at the
+    /// same time don't retry in lockstep. The delay asked for by the server is taken as is, as long
+    /// as it's no longer than the maximum delay.
+    fn delay(&self, attempt: u32, err: &ApiError, rng: &mut Rng) -> Duration {
+        if let Some(retry_after) = err.retry_after() {
+            return cmp::min(retry_after, self.max_delay);
+        }
+
+        let delay = cmp::min(
+            self.base_delay
+                .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1))),
+            self.max_delay,
+        );
+        let half = delay / 2;
+        let max_jitter = u64::try_from(half.as_millis()).unwrap_or(u64::MAX);
+
+        half + Duration::from_millis(rng.u64(0..=max_jitter))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            max_empty_replies: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(16),
+        }
+    }
+}
+
+/// This structure holds the handle to a chat completion request being processed in the background.
+/// Dropping it cancels the request, in the sense that its outcome is discarded and no further
+/// attempts are made.
+pub(crate) struct Worker {
+    /// This field refers to the receiving end of the channel through which the worker thread
+    /// reports back.
+    receiver: Receiver<WorkerMessage>,
+    /// This field refers to the flag shared with the worker thread to notify it that its outcome is
+    /// no longer of interest.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Worker {
+    /// This function spawns a new thread that performs the chat completion request with the given
+    /// backend and request body, retrying it as told by the given policy, and returns the handle to
+    /// it. The given RNG is used to add jitter to the delay between attempts.
+    pub(crate) fn spawn(
+        backend: Arc<dyn ChatBackend>,
+        request: Request,
+        policy: RetryPolicy,
+        rng: Rng,
+    ) -> Self {
+        let (sender, receiver) = channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let job = Job {
+            backend,
+            request,
+            policy,
+            rng,
+            sender,
+            cancelled: Arc::clone(&cancelled),
+        };
+
+        let _ = thread::spawn(move || job.run());
+
+        Self {
+            receiver,
+            cancelled,
+        }
+    }
+
+    /// This function checks, without blocking, whether the worker thread has reported anything
+    /// back.
+    pub(crate) fn poll(&self) -> Option<WorkerMessage> {
+        match self.receiver.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(WorkerMessage::Done(Err(ApiError::WorkerStopped("request"))))
+            }
+        }
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// This structure holds the handle to the list of models being fetched in the background, so that
+/// the model menu can be browsed with the cached list in the meantime.
+pub(crate) struct ModelRefresh {
+    /// This field refers to the receiving end of the channel through which the fetched list is
+    /// reported back.
+    receiver: Receiver<Result<Vec<ModelInfo>, ApiError>>,
+}
+
+impl ModelRefresh {
+    /// This function spawns a new thread that fetches the list of models of the given backend, and
+    /// returns the handle to it.
+    pub(crate) fn spawn(backend: Arc<dyn ChatBackend>) -> Self {
+        let (sender, receiver) = channel();
+        // The outcome is of no interest if the handle has been dropped in the meantime.
+        let _ = thread::spawn(move || sender.send(backend.models()));
+
+        Self { receiver }
+    }
+
+    /// This function checks, without blocking, whether the list has been fetched.
+    pub(crate) fn poll(&self) -> Option<Result<Vec<ModelInfo>, ApiError>> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(ApiError::WorkerStopped("model list"))),
+        }
+    }
+}
+
+/// This structure holds everything the worker thread needs to perform the chat completion request.
+struct Job {
+    /// This field refers to the backend through which the request is performed.
+    backend: Arc<dyn ChatBackend>,
+    /// This field refers to the body of the request.
+    request: Request,
+    /// This field refers to the policy followed to retry the request.
+    policy: RetryPolicy,
+    /// This field refers to the RNG used to add jitter to the delay between attempts.
+    rng: Rng,
+    /// This field refers to the sending end of the channel through which the worker thread reports
+    /// back.
+    sender: Sender<WorkerMessage>,
+    /// This field refers to the flag that tells whether the outcome of the request is still of
+    /// interest.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Job {
+    /// This function performs the request until it succeeds, fails with an error not worth
+    /// retrying, runs out of attempts and fallback models or gets cancelled, and reports the outcome
+    /// back.
+    fn run(mut self) {
+        let mut failures = 0;
+        let mut empty_replies = 0;
+
+        loop {
+            // There's no one left to notify if the request was cancelled. The attempts are counted
+            // for the model in use, as they're bounded.
+            if self.is_cancelled()
+                || self
+                    .sender
+                    .send(WorkerMessage::Attempt(failures + 1))
+                    .is_err()
+            {
+                return;
+            }
+
+            let (outcome, delivered) = self.attempt();
+            let outcome = match outcome {
+                Ok(reply) if reply.content().is_empty() => {
+                    empty_replies += 1;
+                    if empty_replies <= self.policy.max_empty_replies {
+                        continue;
+                    }
+                    Err(ApiError::EmptyReply)
+                }
+                // Once part of the message has been delivered, starting over would only make for a
+                // garbled message. Providers being down are retried before moving on to another
+                // model, as they're likely to be back shortly.
+                Err(err) if err.is_retryable() && !delivered => {
+                    failures += 1;
+                    if failures >= self.policy.max_attempts && self.fall_back() {
+                        failures = 0;
+                        continue;
+                    }
+                    if failures < self.policy.max_attempts {
+                        let delay = self.policy.delay(failures, &err, &mut self.rng);
+                        self.sleep(delay);
+                        continue;
+                    }
+                    Err(err)
+                }
+                Err(err) if err.is_model_down() && !delivered && self.fall_back() => {
+                    failures = 0;
+                    continue;
+                }
+                outcome => outcome,
+            };
+
+            drop(self.sender.send(WorkerMessage::Done(outcome)));
+            return;
+        }
+    }
+
+    /// This function performs a single attempt at the request, streaming the response if asked to,
+    /// and returns its outcome along with whether any piece of the message was delivered.
+    fn attempt(&self) -> (Result<Reply, ApiError>, bool) {
+        if !self.request.stream() {
+            return (self.backend.chat(&self.request), false);
+        }
+
+        let mut delivered = false;
+        let outcome = self.backend.chat_stream(&self.request, &mut |delta| {
+            delivered = true;
+            !self.is_cancelled()
+                && self
+                    .sender
+                    .send(WorkerMessage::Delta(delta.to_owned()))
+                    .is_ok()
+        });
+
+        (outcome, delivered)
+    }
+
+    /// This function switches the request to the next fallback model, if there's any left, and
+    /// reports it back. It returns whether it did so.
+    fn fall_back(&mut self) -> bool {
+        if !self.request.fall_back() {
+            return false;
+        }
+        drop(
+            self.sender
+                .send(WorkerMessage::Fallback(self.request.model().to_owned())),
+        );
+
+        true
+    }
+
+    /// This function returns whether the request has been cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// This function waits for the given amount of time, returning early if the request gets
+    /// cancelled in the meantime.
+    fn sleep(&self, duration: Duration) {
+        // A deadline too far away to be told apart is no reason to wait at all.
+        let Some(deadline) = Instant::now().checked_add(duration) else {
+            return;
+        };
+
+        while !self.is_cancelled() {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            thread::sleep(cmp::min(deadline - now, CANCEL_CHECK_INTERVAL));
+        }
+    }
+}